@@ -1,20 +1,24 @@
 use crate::{
     constants::*,
-    get_with_deviation,
+    get_with_deviation, plant_kinds,
     smart_drawing::{DrawingStrategy, RectangleCorner},
+    terrain_map,
     user_constants::*,
-    Cell, Cells, Cross, CrossId, Plant, PlantId, PlantKind, Zoom,
-    UI_SHOW_PROPERTIES_N,
+    Cell, Cells, Condition, Cross, CrossId, FertilityMap, Food,
+    Genome, Lineage, Plant, PlantId, RememberedFood, Skill,
+    SkillHook, Zoom, UI_SHOW_PROPERTIES_N,
 };
 use macroquad::prelude::{
-    draw_circle, draw_line, draw_rectangle, draw_text, measure_text,
-    rand::gen_range, vec2, Circle, Color, Vec2, Vec3, GREEN, RED,
-    WHITE,
+    draw_circle, draw_circle_lines, draw_line, draw_rectangle,
+    draw_text, measure_text, rand::gen_range, vec2, Circle, Color,
+    Vec2, Vec3, RED, WHITE,
 };
-use rand::{random, rngs::StdRng, seq::IteratorRandom, Rng};
+use rand::{rngs::StdRng, Rng};
+use serde_derive::{Deserialize, Serialize};
 use std::{
-    collections::HashMap, collections::HashSet, f32::consts::PI,
-    f32::consts::SQRT_2, time::Instant,
+    collections::hash_map::Entry, collections::HashMap,
+    collections::HashSet, f32::consts::PI, f32::consts::SQRT_2,
+    time::Instant,
 };
 
 #[derive(Copy, Clone, PartialEq)]
@@ -42,7 +46,16 @@ pub enum Status {
     Idle,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProcreationMode {
+    /// A body splits into 2 children.
+    Asexual,
+    /// 2 relatives mate, and their children get genes from both of them.
+    Sexual,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum EatingStrategy {
     /// When a body sees no food, it stands still.
     Passive,
@@ -50,9 +63,28 @@ pub enum EatingStrategy {
     Active,
 }
 
+impl EatingStrategy {
+    #[inline(always)]
+    /// Get the eating strategy of a child, which may be switched.
+    pub fn get_inherited(self, rng: &mut StdRng) -> Self {
+        if unsafe { EATING_STRATEGY_CHANGE_CHANCE } == 1.0
+            || rng.gen_range(0.0..1.0)
+                <= unsafe { EATING_STRATEGY_CHANGE_CHANCE }
+        {
+            match self {
+                Self::Passive => Self::Active,
+                Self::Active => Self::Passive,
+            }
+        } else {
+            self
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[repr(usize)]
-#[derive(Eq, PartialEq, Hash, Copy, Clone)]
+#[derive(Eq, PartialEq, Hash, Copy, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
 /// https://github.com/kul-sudo/eportal/blob/main/README.md#viruses
 pub enum Virus {
     SpeedVirus,
@@ -63,52 +95,44 @@ impl Virus {
     pub const ALL: [Self; 2] = [Self::SpeedVirus, Self::VisionVirus];
 }
 
-#[derive(Eq, Hash, PartialEq, Copy, Clone)]
-/// https://github.com/kul-sudo/eportal/blob/main/README.md#skills
-pub enum Skill {
-    DoNotCompeteWithRelatives,
-    AliveWhenArrived,
-    ProfitableWhenArrived,
-    PrioritizeFasterChasers,
-    AvoidNewViruses,
-    WillArriveFirst,
-    EatCrossesOfMyType,
-    AvoidInfectedCrosses,
-}
+pub type BodyId = Instant;
 
-impl Skill {
-    pub const ALL: [Self; 8] = [
-        Self::DoNotCompeteWithRelatives,
-        Self::AliveWhenArrived,
-        Self::ProfitableWhenArrived,
-        Self::PrioritizeFasterChasers,
-        Self::AvoidNewViruses,
-        Self::WillArriveFirst,
-        Self::EatCrossesOfMyType,
-        Self::AvoidInfectedCrosses,
-    ];
+/// Where a body is at the start of the tick. Kept in the cells to find the bodies close to
+/// a position without going through all of them.
+#[derive(Clone, Copy)]
+pub struct BodyPosition {
+    pub pos:       Vec2,
+    pub body_type: u16,
 }
 
-pub type BodyId = Instant;
-
 #[derive(Clone, PartialEq)]
 /// https://github.com/kul-sudo/eportal/blob/main/README.md#properties
 pub struct Body {
-    pub pos:                 Vec2,
-    pub energy:              f32,
-    pub speed:               f32,
-    pub vision_distance:     f32,
-    pub eating_strategy:     EatingStrategy,
-    pub division_threshold:  f32,
-    pub skills:              HashSet<Skill>,
-    pub viruses:             HashMap<Virus, f32>,
-    pub color:               Color,
-    pub status:              Status,
-    pub body_type:           u16,
-    pub lifespan:            f32,
-    initial_speed:           f32,
-    initial_vision_distance: f32,
-    pub followed_by:         HashMap<BodyId, Self>,
+    pub pos:             Vec2,
+    pub energy:          f32,
+    pub speed:           f32,
+    pub vision_distance: f32,
+    pub genome:          Genome,
+    pub viruses:         HashMap<Virus, f32>,
+    pub status:          Status,
+    pub body_type:       u16,
+    pub lifespan:        f32,
+    pub followed_by:     HashMap<BodyId, Self>,
+    /// The food the body has seen, used by `Skill::Memory`.
+    pub memory:          Vec<RememberedFood>,
+    /// How many relatives are close to the body, used by `Skill::Herd`.
+    pub herd_size:       usize,
+    /// When the body started striking from an ambush, used by `Skill::Ambush`.
+    pub strike:          Option<Instant>,
+    /// How the body moved last time.
+    pub velocity:        Vec2,
+    /// The angle of the direction the body looks in.
+    pub heading:         f32,
+    pub lineage:         Lineage,
+    /// The kind of the plant whose seed the body carries and when the plant was eaten.
+    pub seed:            Option<(usize, Instant)>,
+    /// The condition the body is within.
+    pub condition:       Option<Condition>,
 }
 
 #[macro_export]
@@ -116,7 +140,7 @@ macro_rules! get_visible {
     ($body:expr, $cells:expr, $x:expr, $visible_x:expr) => {
         // Using these for ease of development
         let (a, b) = ($body.pos.x, $body.pos.y);
-        let r = $body.vision_distance;
+        let r = $body.get_vision_distance();
         let (w, h) = ($cells.cell_width, $cells.cell_height);
         let (m, n) = ($cells.columns, $cells.rows);
 
@@ -183,9 +207,10 @@ macro_rules! get_visible {
         for (x_id, x) in
         $x.get(&Cell { i, j }).unwrap()
         {
-        if fully_covered
+        if (fully_covered
         || $body.pos.distance(x.pos)
-        <= $body.vision_distance
+        <= $body.get_vision_distance())
+        && $body.can_see(x.pos)
         {
         $visible_x.insert(x_id, x);
         }
@@ -202,84 +227,27 @@ impl Body {
     pub fn new(
         pos: Vec2,
         energy: Option<f32>,
-        eating_strategy: EatingStrategy,
-        division_threshold: Option<f32>,
-        skills: Option<HashSet<Skill>>,
-        color: Color,
+        genome: Genome,
         body_type: u16,
         viruses: Option<HashMap<Virus, f32>>,
-        initial_speed: Option<f32>,
-        initial_vision_distance: Option<f32>,
+        lineage: Lineage,
         rng: &mut StdRng,
     ) -> Self {
-        let speed = get_with_deviation(
-            match initial_speed {
-                Some(initial_speed) => initial_speed,
-                None => unsafe { AVERAGE_SPEED },
-            },
-            rng,
-        );
-
-        let vision_distance = get_with_deviation(
-            match initial_vision_distance {
-                Some(initial_vision_distance) => {
-                    initial_vision_distance
-                }
-                None => unsafe { AVERAGE_VISION_DISTANCE },
-            },
-            rng,
-        );
-
         let mut body = Self {
             pos,
             energy: match energy {
-                Some(energy) => energy / 2.0,
+                Some(energy) => energy,
                 None => {
                     get_with_deviation(unsafe { AVERAGE_ENERGY }, rng)
                 }
             },
-            speed,
-            initial_speed: speed,
-            vision_distance,
-            initial_vision_distance: vision_distance,
-            eating_strategy,
-            division_threshold: get_with_deviation(
-                match division_threshold {
-                    Some(division_threshold) => division_threshold,
-                    None => unsafe { AVERAGE_DIVISION_THRESHOLD },
-                },
-                rng,
-            ),
-            skills: match skills {
-                Some(mut skills) => {
-                    if rng.gen_range(0.0..1.0)
-                        <= unsafe { SKILLS_CHANGE_CHANCE }
-                    {
-                        if random::<bool>() {
-                            if let Some(random_skill) =
-                                HashSet::from(Skill::ALL)
-                                    .difference(&skills)
-                                    .collect::<HashSet<_>>()
-                                    .iter()
-                                    .choose(rng)
-                            {
-                                skills.insert(**random_skill);
-                            }
-                        } else if let Some(random_skill) =
-                            skills.clone().iter().choose(rng)
-                        {
-                            skills.remove(random_skill);
-                        }
-                    }
-
-                    skills
-                }
-                None => HashSet::with_capacity(Skill::ALL.len()),
-            },
-            color,
+            speed: genome.speed,
+            vision_distance: genome.vision_distance,
+            genome,
             status: Status::Idle,
             body_type,
             lifespan: unsafe { LIFESPAN },
+            lineage,
             viruses: match viruses {
                 Some(viruses) => viruses,
                 None => {
@@ -319,6 +287,13 @@ impl Body {
                 }
             },
             followed_by: HashMap::new(),
+            memory: Vec::new(),
+            herd_size: 0,
+            strike: None,
+            seed: None,
+            condition: None,
+            velocity: Vec2::ZERO,
+            heading: rng.gen_range(0.0..2.0 * PI),
         };
 
         // Applying the effect of the viruses
@@ -346,9 +321,10 @@ impl Body {
 
     #[inline(always)]
     pub fn draw(&self) {
-        let side_length_half = OBJECT_RADIUS / SQRT_2;
+        let radius = self.get_radius();
+        let side_length_half = radius / SQRT_2;
 
-        match self.eating_strategy {
+        match self.genome.eating_strategy {
             EatingStrategy::Active => {
                 let side_length = side_length_half * 2.0;
                 draw_rectangle(
@@ -356,14 +332,14 @@ impl Body {
                     self.pos.y - side_length_half,
                     side_length,
                     side_length,
-                    self.color,
+                    self.genome.color,
                 );
             }
             EatingStrategy::Passive => draw_circle(
                 self.pos.x,
                 self.pos.y,
-                OBJECT_RADIUS,
-                self.color,
+                radius,
+                self.genome.color,
             ),
         }
 
@@ -385,7 +361,7 @@ impl Body {
         if unsafe { SHOW_DIVISION_THRESHOLD } {
             to_display_components.push(format!(
                 "dt = {}",
-                self.division_threshold as usize
+                self.genome.division_threshold as usize
             ));
         }
 
@@ -404,7 +380,8 @@ impl Body {
         if unsafe { SHOW_SKILLS } {
             to_display_components.push(format!(
                 "skills = {:?}",
-                self.skills
+                self.genome
+                    .skills
                     .iter()
                     .map(|skill| *skill as u8)
                     .collect::<Vec<_>>()
@@ -434,7 +411,7 @@ impl Body {
                     )
                     .width
                         / 2.0,
-                self.pos.y - OBJECT_RADIUS - MIN_GAP,
+                self.pos.y - self.get_radius() - MIN_GAP,
                 unsafe { BODY_INFO_FONT_SIZE } as f32,
                 WHITE,
             );
@@ -445,10 +422,16 @@ impl Body {
     /// Get the body infected with every virus it doesnn't have yet.
     pub fn get_viruses(&mut self, viruses: &HashMap<Virus, f32>) {
         for virus in viruses.keys() {
-            if !self.viruses.contains_key(virus) {
-                self.viruses.insert(*virus, 0.0);
-                self.apply_virus(*virus);
-            }
+            self.get_virus(*virus);
+        }
+    }
+
+    #[inline(always)]
+    /// Get the body infected with the virus unless it already has it.
+    pub fn get_virus(&mut self, virus: Virus) {
+        if let Entry::Vacant(entry) = self.viruses.entry(virus) {
+            entry.insert(0.0);
+            self.apply_virus(virus);
         }
     }
 
@@ -489,7 +472,7 @@ impl Body {
                 drawing_strategy.vision_distance = Circle::new(
                     self.pos.x,
                     self.pos.y,
-                    self.vision_distance,
+                    self.get_vision_distance(),
                 )
                 .overlaps_rect(&zoom.rect.unwrap());
 
@@ -616,12 +599,25 @@ impl Body {
         body_id: &BodyId,
         cells: &Cells,
         bodies: &mut HashMap<BodyId, Self>,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
         crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
         plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
         area_size: &Vec2,
         rng: &mut StdRng,
     ) {
-        match self.eating_strategy {
+        for skill in Skill::ALL {
+            if skill.has_hook(SkillHook::Idle)
+                && self.genome.skills.contains(&skill)
+                && skill.handle_idle(
+                    self, body_id, cells, bodies, body_cells,
+                    crosses, plants, area_size,
+                )
+            {
+                return;
+            }
+        }
+
+        match self.genome.eating_strategy {
             EatingStrategy::Active => {
                 if !matches!(self.status, Status::Walking(..)) {
                     let walking_angle: f32 =
@@ -642,271 +638,1462 @@ impl Body {
                 }
 
                 if let Status::Walking(pos_deviation) = self.status {
-                    self.pos.x += pos_deviation.x;
-                    self.pos.y += pos_deviation.y;
+                    self.move_with(pos_deviation);
                 }
 
                 self.wrap(area_size);
             }
-            EatingStrategy::Passive => self.set_status(
-                Status::Idle,
-                &body_id,
-                &cells,
-                bodies,
-                crosses,
-                plants,
-            ),
+            EatingStrategy::Passive => {
+                self.set_status(
+                    Status::Idle,
+                    &body_id,
+                    &cells,
+                    bodies,
+                    crosses,
+                    plants,
+                );
+
+                self.move_with(Vec2::ZERO);
+                self.wrap(area_size);
+            }
         }
     }
 
     #[inline(always)]
-    /// Handle the energy. The function returns if the body has run out of energy.
-    pub fn handle_energy(
-        &mut self,
-        body_id: &BodyId,
-        removed_bodies: &mut HashSet<BodyId>,
-    ) -> bool {
-        // The mass is proportional to the energy; to keep the mass up, energy is spent
-        self.energy -= unsafe { ENERGY_SPENT_CONST_FOR_MASS }
-            * self.energy
-            + unsafe { ENERGY_SPENT_CONST_FOR_SKILLS }
-                * self.skills.len() as f32
-            + unsafe { ENERGY_SPENT_CONST_FOR_VISION_DISTANCE }
-                * self.vision_distance.powi(2);
+    /// Move the body with `desired_velocity`. With inertia, the velocity can only change as
+    /// fast as the genome of the body lets it, and changing it costs energy.
+    pub fn move_with(&mut self, desired_velocity: Vec2) {
+        let terrain_map = terrain_map();
+        let desired_velocity = desired_velocity
+            * terrain_map.get(&self.pos).get_speed_multiplier()
+            * if self.condition == Some(Condition::Winter) {
+                unsafe { WINTER_SPEED_MULTIPLIER }
+            } else {
+                1.0
+            };
 
-        if self.status != Status::Idle {
-            self.energy -= unsafe { ENERGY_SPENT_CONST_FOR_MOVEMENT }
-                * self.speed.powi(2)
-                * self.energy;
-        }
+        if unsafe { INERTIA } {
+            let current_speed = self.velocity.length();
+            let desired_speed = desired_velocity.length();
+
+            let direction = if current_speed == 0.0 {
+                desired_velocity.normalize_or_zero()
+            } else if desired_speed == 0.0 {
+                // Slowing down without turning
+                self.velocity / current_speed
+            } else {
+                let current_direction = self.velocity / current_speed;
+                let angle = current_direction
+                    .angle_between(desired_velocity)
+                    .clamp(
+                        -self.genome.max_turning_rate,
+                        self.genome.max_turning_rate,
+                    );
 
-        if self.energy <= 0.0 {
-            removed_bodies.insert(*body_id);
-            true
+                Vec2::from_angle(angle).rotate(current_direction)
+            };
+
+            let speed = current_speed
+                + (desired_speed - current_speed).clamp(
+                    -self.genome.max_acceleration,
+                    self.genome.max_acceleration,
+                );
+
+            let velocity = direction * speed;
+
+            self.energy = (self.energy
+                - unsafe { ACCELERATION_ENERGY_COST }
+                    * (velocity - self.velocity).length())
+            .max(0.0);
+            self.velocity = velocity;
         } else {
-            false
+            self.velocity = desired_velocity;
         }
-    }
 
-    #[inline(always)]
-    pub fn handle_lifespan(&mut self) {
-        if self.status != Status::Idle {
-            self.lifespan = (self.lifespan
-                - unsafe { CONST_FOR_LIFESPAN }
-                    * self.speed.powi(2)
-                    * self.energy)
-                .max(0.0)
-        }
-    }
+        // Slide along the rocks instead of walking into them
+        if terrain_map.is_blocked(&(self.pos + self.velocity))
+            && !terrain_map.is_blocked(&self.pos)
+        {
+            let (along_x, along_y) = (
+                vec2(self.velocity.x, 0.0),
+                vec2(0.0, self.velocity.y),
+            );
 
-    #[inline(always)]
-    /// Handle procreation and return if one has happened.
-    pub fn handle_procreation(
-        &mut self,
-        body_id: &BodyId,
-        new_bodies: &mut HashMap<BodyId, Self>,
-        removed_bodies: &mut HashSet<BodyId>,
-        rng: &mut StdRng,
-    ) -> bool {
-        if self.energy > self.division_threshold {
-            for _ in 0..2 {
-                new_bodies.insert(
-                    Instant::now(),
-                    Body::new(
-                        self.pos,
-                        Some(self.energy),
-                        self.eating_strategy,
-                        Some(self.division_threshold),
-                        Some(self.skills.clone()),
-                        self.color,
-                        self.body_type,
-                        Some(self.viruses.clone()),
-                        Some(self.initial_speed),
-                        Some(self.initial_vision_distance),
-                        rng,
-                    ),
-                );
-            }
+            self.velocity =
+                if self.velocity.x.abs() >= self.velocity.y.abs() {
+                    [along_x, along_y]
+                } else {
+                    [along_y, along_x]
+                }
+                .into_iter()
+                .find(|velocity| {
+                    !terrain_map.is_blocked(&(self.pos + *velocity))
+                })
+                .unwrap_or(Vec2::ZERO);
+        }
 
-            removed_bodies.insert(*body_id);
+        self.pos += self.velocity;
 
-            true
+        if self.velocity != Vec2::ZERO {
+            self.heading = self.velocity.y.atan2(self.velocity.x);
         } else {
-            false
+            // Look around not to miss what's behind
+            self.heading = (self.heading + IDLE_TURNING_RATE)
+                .rem_euclid(2.0 * PI);
         }
     }
 
     #[inline(always)]
-    pub fn get_spent_energy(&self, time: f32) -> f32 {
-        time * unsafe { ENERGY_SPENT_CONST_FOR_MOVEMENT }
-            * self.speed.powi(2)
-            * self.energy
-            + unsafe { ENERGY_SPENT_CONST_FOR_MASS } * self.energy
-            + unsafe { ENERGY_SPENT_CONST_FOR_SKILLS }
-                * self.skills.len() as f32
-            + unsafe { ENERGY_SPENT_CONST_FOR_VISION_DISTANCE }
-                * self.vision_distance.powi(2)
+    /// Whether the body can see `pos` if it's close enough: it has to be within the vision
+    /// angle, and no rocks can be in the way.
+    pub fn can_see(&self, pos: Vec2) -> bool {
+        self.is_in_field_of_view(pos)
+            && terrain_map().is_line_clear(&self.pos, &pos)
     }
 
-    /// Generate a random position until it suits certain creteria.
-    pub fn randomly_spawn_body(
-        bodies: &mut HashMap<Instant, Self>,
-        area_size: &Vec2,
-        eating_strategy: EatingStrategy,
-        body_type: usize,
-        rng: &mut StdRng,
-    ) {
-        let mut pos = Vec2::default();
+    #[inline(always)]
+    /// Whether `pos` is within the vision angle of the body, whatever the distance.
+    pub fn is_in_field_of_view(&self, pos: Vec2) -> bool {
+        let direction = pos - self.pos;
+
+        self.genome.vision_angle >= 2.0 * PI
+            || direction == Vec2::ZERO
+            || Vec2::from_angle(self.heading)
+                .angle_between(direction)
+                .abs()
+                <= self.genome.vision_angle / 2.0
+    }
 
-        // Make sure the position is far enough from the rest of the bodies and the borders of the area
-        while {
-            pos.x = rng.gen_range(0.0..area_size.x);
-            pos.y = rng.gen_range(0.0..area_size.y);
-            (pos.x <= OBJECT_RADIUS + MIN_GAP
-                || pos.x >= area_size.x - OBJECT_RADIUS - MIN_GAP)
-                || (pos.y <= OBJECT_RADIUS + MIN_GAP
-                    || pos.y >= area_size.y - OBJECT_RADIUS - MIN_GAP)
-                || bodies.values().any(|body| {
-                    body.pos.distance(pos)
-                        < OBJECT_RADIUS * 2.0 + MIN_GAP
-                })
-        } {}
+    #[inline(always)]
+    /// How far the body sees now, which is less within fog.
+    pub fn get_vision_distance(&self) -> f32 {
+        self.vision_distance
+            * if self.condition == Some(Condition::Fog) {
+                unsafe { FOG_VISION_DISTANCE_MULTIPLIER }
+            } else {
+                1.0
+            }
+    }
 
-        // Make sure the color is different enough
-        let real_color_gap = COLOR_GAP
-            / ((unsafe { BODIES_N } + 3) as f32).powf(1.0 / 3.0);
+    #[inline(always)]
+    /// The energy constantly spent on vision, which depends on the area the body sees.
+    /// Fog doesn't change it, because the eyes stay the same.
+    pub fn get_vision_energy_cost(&self) -> f32 {
+        self.vision_distance.powi(2)
+            * unsafe { ENERGY_SPENT_CONST_FOR_VISION_DISTANCE }
+            * self.genome.vision_angle
+            / (2.0 * PI)
+    }
 
-        let mut color = Color::from_rgba(
-            gen_range(COLOR_MIN, COLOR_MAX),
-            gen_range(COLOR_MIN, COLOR_MAX),
-            gen_range(COLOR_MIN, COLOR_MAX),
-            255,
-        );
+    #[inline(always)]
+    /// Draw what the body sees: a circle or a cone in front of it.
+    pub fn draw_vision(&self) {
+        if self.genome.vision_angle >= 2.0 * PI {
+            draw_circle_lines(
+                self.pos.x,
+                self.pos.y,
+                self.get_vision_distance(),
+                2.0,
+                self.genome.color,
+            );
 
-        let green_rgb = Vec3 {
-            x: GREEN.r,
-            y: GREEN.g,
-            z: GREEN.b,
-        };
+            return;
+        }
 
-        let red_rgb = Vec3 {
-            x: RED.r,
-            y: RED.g,
-            z: RED.b,
+        let half_angle = self.genome.vision_angle / 2.0;
+        let point_at = |angle: f32| {
+            self.pos
+                + Vec2::from_angle(angle) * self.get_vision_distance()
         };
 
-        while bodies.values().any(|body| {
-            let current_body_rgb = Vec3 {
-                x: body.color.r,
-                y: body.color.g,
-                z: body.color.b,
-            };
-            current_body_rgb.distance(green_rgb) < real_color_gap
-                || current_body_rgb.distance(red_rgb) < real_color_gap
-                || current_body_rgb.distance(Vec3 {
-                    x: color.r,
-                    y: color.g,
-                    z: color.b,
-                }) < real_color_gap
-        }) {
-            color = Color::from_rgba(
-                gen_range(COLOR_MIN, COLOR_MAX),
-                gen_range(COLOR_MIN, COLOR_MAX),
-                gen_range(COLOR_MIN, COLOR_MAX),
-                255,
-            )
+        for angle in
+            [self.heading - half_angle, self.heading + half_angle]
+        {
+            let edge = point_at(angle);
+            draw_line(
+                self.pos.x,
+                self.pos.y,
+                edge.x,
+                edge.y,
+                2.0,
+                self.genome.color,
+            );
         }
 
-        bodies.insert(
-            Instant::now(),
-            Body::new(
-                pos,
-                None,
-                eating_strategy,
-                None,
-                None,
-                color,
-                body_type as u16,
-                None,
-                None,
-                None,
-                rng,
-            ),
-        );
+        for i in 0..VISION_CONE_SEGMENTS_N {
+            let from = point_at(
+                self.heading - half_angle
+                    + self.genome.vision_angle * i as f32
+                        / VISION_CONE_SEGMENTS_N as f32,
+            );
+            let to = point_at(
+                self.heading - half_angle
+                    + self.genome.vision_angle * (i + 1) as f32
+                        / VISION_CONE_SEGMENTS_N as f32,
+            );
+            draw_line(
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+                2.0,
+                self.genome.color,
+            );
+        }
     }
 
-    pub fn set_status(
+    #[inline(always)]
+    /// Walk to the most worthwhile remembered food. Returns whether there's any.
+    pub fn handle_memory_idle(
         &mut self,
-        status: Status,
         body_id: &BodyId,
         cells: &Cells,
         bodies: &mut HashMap<BodyId, Self>,
         crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
         plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
-    ) {
-        Body::followed_by_cleanup(
-            &body_id, &cells, bodies, crosses, plants, None,
+        area_size: &Vec2,
+    ) -> bool {
+        let pos = self.pos;
+        let speed = self.speed;
+
+        self.memory.retain(|remembered_food| {
+            remembered_food.timestamp.elapsed().as_secs()
+                <= unsafe { MEMORY_LIFETIME }
+                // There's nothing left there if the body has got there and sees no food
+                && pos.distance(remembered_food.pos) > speed
+        });
+
+        // Fresher memories, closer places and more energy are better
+        let Some(best_remembered_food) =
+            self.memory.iter().max_by(|a, b| {
+                a.get_value(&pos).total_cmp(&b.get_value(&pos))
+            })
+        else {
+            return false;
+        };
+
+        let pos_deviation = (best_remembered_food.pos - pos)
+            .normalize_or_zero()
+            * speed;
+
+        self.set_status(
+            Status::Walking(pos_deviation),
+            body_id,
+            cells,
+            bodies,
+            crosses,
+            plants,
         );
-        self.status = status;
+
+        self.move_with(pos_deviation);
+        self.wrap(area_size);
+
+        true
     }
 
     #[inline(always)]
-    pub fn find_closest_plant<'a>(
-        &self,
-        visible_plants: &'a [(&&'a PlantId, &&'a Plant)],
-        plant_kind: PlantKind,
-    ) -> Option<&'a (&&'a PlantId, &&'a Plant)> {
-        visible_plants
+    /// Keep the body together with the visible relatives, but not too close to them. Returns
+    /// whether the body has moved.
+    pub fn handle_herd_idle(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Self>,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        area_size: &Vec2,
+    ) -> bool {
+        let mut visible_bodies: HashMap<&BodyId, &BodyPosition> =
+            HashMap::new();
+
+        get_visible!(self, cells, body_cells, visible_bodies);
+
+        let relatives_pos = visible_bodies
             .iter()
-            .filter(|(_, plant)| plant.kind == plant_kind)
-            .min_by(|(_, a), (_, b)| {
-                self.pos
-                    .distance(a.pos)
-                    .partial_cmp(&self.pos.distance(b.pos))
-                    .unwrap()
+            .filter(|(other_body_id, other_body)| {
+                **other_body_id != body_id
+                    && other_body.body_type == self.body_type
             })
+            .map(|(_, other_body)| other_body.pos)
+            .collect::<Vec<_>>();
+
+        if relatives_pos.is_empty() {
+            return false;
+        }
+
+        let centroid = relatives_pos.iter().sum::<Vec2>()
+            / relatives_pos.len() as f32;
+
+        // Cohesion
+        let mut direction = if self.pos.distance(centroid)
+            > unsafe { HERD_SEPARATION_DISTANCE }
+        {
+            (centroid - self.pos).normalize_or_zero()
+        } else {
+            Vec2::ZERO
+        };
+
+        // Separation
+        for relative_pos in &relatives_pos {
+            if self.pos.distance(*relative_pos)
+                < unsafe { HERD_SEPARATION_DISTANCE }
+            {
+                direction +=
+                    (self.pos - *relative_pos).normalize_or_zero();
+            }
+        }
+
+        let direction = direction.normalize_or_zero();
+
+        if direction == Vec2::ZERO {
+            return false;
+        }
+
+        let pos_deviation = direction * self.speed;
+
+        self.set_status(
+            Status::Walking(pos_deviation),
+            body_id,
+            cells,
+            bodies,
+            crosses,
+            plants,
+        );
+
+        self.move_with(pos_deviation);
+        self.wrap(area_size);
+
+        true
     }
 
     #[inline(always)]
-    pub fn handle_profitable_when_arrived_body(
-        &self,
-        other_body: &Body,
+    /// Hide in the biggest visible cluster of plants. Returns whether the body is passive.
+    pub fn handle_ambush_idle(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Self>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        area_size: &Vec2,
     ) -> bool {
-        if self.skills.contains(&Skill::ProfitableWhenArrived) {
-            let divisor = self.speed - other_body.speed;
+        if self.genome.eating_strategy != EatingStrategy::Passive {
+            return false;
+        }
 
-            if divisor <= 0.0 {
-                return false;
+        let mut visible_plants: HashMap<&PlantId, &Plant> =
+            HashMap::new();
+
+        get_visible!(self, cells, plants, visible_plants);
+
+        let mut clusters: HashMap<Cell, Vec<Vec2>> = HashMap::new();
+        for plant in visible_plants.values() {
+            clusters
+                .entry(cells.get_cell_by_pos(&plant.pos))
+                .or_default()
+                .push(plant.pos);
+        }
+
+        let cluster_pos = clusters
+            .values()
+            .max_by_key(|cluster| cluster.len())
+            .map(|cluster| {
+                cluster.iter().sum::<Vec2>() / cluster.len() as f32
+            });
+
+        match cluster_pos {
+            Some(cluster_pos)
+                if self.pos.distance(cluster_pos) > self.speed =>
+            {
+                let pos_deviation = (cluster_pos - self.pos)
+                    .normalize_or_zero()
+                    * self.speed;
+
+                self.set_status(
+                    Status::Walking(pos_deviation),
+                    body_id,
+                    cells,
+                    bodies,
+                    crosses,
+                    plants,
+                );
+
+                self.move_with(pos_deviation);
+                self.wrap(area_size);
             }
+            _ => {
+                self.set_status(
+                    Status::Idle,
+                    body_id,
+                    cells,
+                    bodies,
+                    crosses,
+                    plants,
+                );
 
-            self.get_spent_energy(
-                self.pos.distance(other_body.pos) / divisor,
-            ) < other_body.energy
-        } else {
-            true
+                self.move_with(Vec2::ZERO);
+            }
         }
+
+        true
     }
 
     #[inline(always)]
-    pub fn handle_profitable_when_arrived_plant(
-        &self,
-        plant: &Plant,
-    ) -> bool {
-        if self.skills.contains(&Skill::ProfitableWhenArrived) {
-            self.get_spent_energy(
-                self.pos.distance(plant.pos) / self.speed,
-            ) < plant.get_contained_energy()
+    /// Whether the body is waiting for prey in an ambush.
+    pub fn is_ambushing(&self) -> bool {
+        self.status == Status::Idle
+            && self.genome.eating_strategy == EatingStrategy::Passive
+            && self.genome.skills.contains(&Skill::Ambush)
+    }
+
+    #[inline(always)]
+    /// The part of the vision distance of other bodies the body can be seen within.
+    pub fn get_visibility(&self) -> f32 {
+        if self.is_ambushing() {
+            unsafe { AMBUSH_VISIBILITY }
         } else {
-            true
+            1.0
         }
     }
 
     #[inline(always)]
-    pub fn handle_profitable_when_arrived_cross(
+    /// Strike if the body is in an ambush and the food is a body close enough. Returns
+    /// whether the body keeps waiting in the ambush instead of going for the food, which
+    /// it does until the prey comes close enough.
+    pub fn handle_ambush(&mut self, food: &FoodInfo) -> bool {
+        if !self.is_ambushing() || food.food_type != ObjectType::Body
+        {
+            return false;
+        }
+
+        if self.pos.distance(food.pos)
+            <= unsafe { AMBUSH_STRIKE_DISTANCE }
+        {
+            self.strike = Some(Instant::now());
+            false
+        } else {
+            true
+        }
+    }
+
+    #[inline(always)]
+    /// Stop the strike when it's over.
+    pub fn handle_strike(&mut self) {
+        if self.strike.is_some_and(|strike| {
+            strike.elapsed().as_secs_f32()
+                > unsafe { AMBUSH_STRIKE_DURATION }
+        }) {
+            self.strike = None;
+        }
+    }
+
+    #[inline(always)]
+    /// The speed the body moves with now, which is greater while striking from an ambush.
+    pub fn get_speed(&self) -> f32 {
+        self.speed
+            * if self.strike.is_some() {
+                unsafe { AMBUSH_STRIKE_SPEED_MULTIPLIER }
+            } else {
+                1.0
+            }
+    }
+
+    #[inline(always)]
+    /// Drop the seed the body carries when it's been carried long enough. Returns if a plant
+    /// has grown from it.
+    #[allow(clippy::too_many_arguments)]
+    pub fn handle_seed(
+        &mut self,
+        body_id: &BodyId,
+        bodies: &HashMap<BodyId, Body>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        cells: &Cells,
+        fertility_map: &FertilityMap,
+        area_size: &Vec2,
+        rng: &mut StdRng,
+    ) -> bool {
+        if let Some((kind, timestamp)) = self.seed {
+            if timestamp.elapsed().as_secs_f32()
+                >= unsafe { SEED_CARRY_TIME }
+            {
+                self.seed = None;
+
+                // The seed is dropped behind the body so that the plant doesn't grow under it
+                let pos = self.pos
+                    - Vec2::from_angle(self.heading)
+                        * (OBJECT_RADIUS * 2.0 + MIN_GAP);
+
+                if (pos.x <= OBJECT_RADIUS + MIN_GAP
+                    || pos.x >= area_size.x - OBJECT_RADIUS - MIN_GAP)
+                    || (pos.y <= OBJECT_RADIUS + MIN_GAP
+                        || pos.y
+                            >= area_size.y - OBJECT_RADIUS - MIN_GAP)
+                    || bodies.iter().any(|(other_body_id, body)| {
+                        other_body_id != body_id
+                            && body.pos.distance(pos)
+                                <= OBJECT_RADIUS * 2.0 + MIN_GAP
+                    })
+                    || terrain_map().is_blocked(&pos)
+                {
+                    return false;
+                }
+
+                let cell = cells.get_cell_by_pos(&pos);
+                if !Plant::is_cell_full(plants, cells, &pos)
+                    && fertility_map.allows_spawn(&cell, rng)
+                    && plant_kinds()[kind]
+                        .can_grow_with(fertility_map.get(&cell))
+                {
+                    Plant::spawn_plant(
+                        plants, cells, pos, kind, false,
+                    );
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    #[inline(always)]
+    /// Handle the energy. The function returns if the body has run out of energy.
+    pub fn handle_energy(
+        &mut self,
+        body_id: &BodyId,
+        removed_bodies: &mut HashSet<BodyId>,
+    ) -> bool {
+        // The mass is proportional to the energy; to keep the mass up, energy is spent
+        self.energy -= (unsafe { ENERGY_SPENT_CONST_FOR_MASS }
+            * self.energy
+            + self.get_skills_energy_cost()
+            + self.get_vision_energy_cost())
+            * if self.is_ambushing() {
+                unsafe { AMBUSH_IDLE_ENERGY_MULTIPLIER }
+            } else {
+                1.0
+            }
+            * if self.condition == Some(Condition::Winter) {
+                unsafe { WINTER_ENERGY_SPENT_MULTIPLIER }
+            } else {
+                1.0
+            };
+
+        // With inertia, idle bodies may still be coasting
+        if self.velocity != Vec2::ZERO {
+            self.energy -= self.get_movement_energy_cost()
+                * terrain_map()
+                    .get(&self.pos)
+                    .get_energy_multiplier();
+        }
+
+        if self.energy <= 0.0 {
+            removed_bodies.insert(*body_id);
+            true
+        } else {
+            false
+        }
+    }
+
+    #[inline(always)]
+    pub fn handle_lifespan(&mut self, tick_duration: f32) {
+        let is_heatwave = self.condition == Some(Condition::Heatwave);
+
+        if self.status != Status::Idle {
+            self.lifespan -= unsafe { CONST_FOR_LIFESPAN }
+                * self.get_speed().powi(2)
+                * self.energy
+                * if is_heatwave {
+                    unsafe { HEATWAVE_LIFESPAN_LOSS_MULTIPLIER }
+                } else {
+                    1.0
+                };
+        }
+
+        // The heatwave makes the body age faster even if it stands still
+        if is_heatwave {
+            self.lifespan -=
+                (unsafe { HEATWAVE_LIFESPAN_LOSS_MULTIPLIER } - 1.0)
+                    * tick_duration;
+        }
+
+        self.lifespan = self.lifespan.max(0.0);
+    }
+
+    #[inline(always)]
+    /// Maybe get infected with the virus of the plague the body is within.
+    pub fn handle_plague(&mut self, rng: &mut StdRng) {
+        if self.condition == Some(Condition::Plague)
+            && rng.gen_range(0.0..1.0)
+                < unsafe { PLAGUE_INFECTION_CHANCE }
+        {
+            self.get_virus(unsafe { PLAGUE_VIRUS });
+        }
+    }
+
+    #[inline(always)]
+    /// Handle procreation and return if one has happened.
+    pub fn handle_procreation(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Self>,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        new_bodies: &mut HashMap<BodyId, Self>,
+        removed_bodies: &mut HashSet<BodyId>,
+        area_size: &Vec2,
+        rng: &mut StdRng,
+    ) -> bool {
+        // The children are born where the body is, so it can't be inside a rock, which it
+        // can end up in after wrapping around the area
+        if self.energy <= self.genome.division_threshold
+            || removed_bodies.contains(body_id)
+            || terrain_map().is_blocked(&self.pos)
+        {
+            return false;
+        }
+
+        match unsafe { PROCREATION_MODE } {
+            ProcreationMode::Asexual => {
+                self.divide(body_id, new_bodies, removed_bodies, rng)
+            }
+            ProcreationMode::Sexual => self.handle_mating(
+                body_id,
+                cells,
+                bodies,
+                body_cells,
+                crosses,
+                plants,
+                new_bodies,
+                removed_bodies,
+                area_size,
+                rng,
+            ),
+        }
+    }
+
+    #[inline(always)]
+    /// Have a litter of children on its own. Returns whether the body has had it, which it
+    /// doesn't if the children would have no energy.
+    pub fn divide(
+        &mut self,
+        body_id: &BodyId,
+        new_bodies: &mut HashMap<BodyId, Self>,
+        removed_bodies: &mut HashSet<BodyId>,
+        rng: &mut StdRng,
+    ) -> bool {
+        let litter_size = self.genome.litter_size;
+        let child_energy = Body::get_child_energy(
+            self.get_litter_energy(),
+            litter_size,
+        );
+
+        if child_energy == 0.0 {
+            return false;
+        }
+
+        // The body stays where it is to have the litter
+        self.velocity = Vec2::ZERO;
+        self.take_litter_energy(body_id, removed_bodies);
+
+        for _ in 0..litter_size {
+            new_bodies.insert(
+                Instant::now(),
+                Body::new(
+                    self.pos,
+                    Some(child_energy),
+                    self.genome.mutate(rng),
+                    self.body_type,
+                    Some(self.viruses.clone()),
+                    Lineage::child_of(body_id, &self.lineage),
+                    rng,
+                ),
+            );
+        }
+
+        true
+    }
+
+    #[inline(always)]
+    /// The part of the energy the body would give to its children.
+    pub fn get_litter_energy(&self) -> f32 {
+        self.energy * self.genome.litter_energy_share
+    }
+
+    #[inline(always)]
+    /// Take the part of the energy the body gives to its children. The body disappears if
+    /// what it's left with isn't enough to live.
+    pub fn take_litter_energy(
+        &mut self,
+        body_id: &BodyId,
+        removed_bodies: &mut HashSet<BodyId>,
+    ) -> f32 {
+        let litter_energy = self.get_litter_energy();
+        self.energy -= litter_energy;
+
+        if self.energy < unsafe { MIN_ENERGY } {
+            removed_bodies.insert(*body_id);
+        }
+
+        litter_energy
+    }
+
+    #[inline(always)]
+    /// The energy every child of a litter gets.
+    pub fn get_child_energy(
+        litter_energy: f32,
+        litter_size: usize,
+    ) -> f32 {
+        (litter_energy / litter_size as f32
+            - unsafe { PROCREATION_ENERGY_COST })
+        .max(0.0)
+    }
+
+    #[inline(always)]
+    /// Mate with the closest visible relative that is ready to procreate, or walk to it.
+    /// A body that sees no such relative doesn't procreate. Returns whether the body has
+    /// done any of it.
+    pub fn handle_mating(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Self>,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        new_bodies: &mut HashMap<BodyId, Self>,
+        removed_bodies: &mut HashSet<BodyId>,
+        area_size: &Vec2,
+        rng: &mut StdRng,
+    ) -> bool {
+        let vision_distance = self.get_vision_distance();
+
+        let Some((partner_id, partner)) = cells
+            .get_cells_within(&self.pos, vision_distance)
+            .flat_map(|cell| body_cells.get(&cell).unwrap())
+            .filter(|(other_body_id, other_body)| {
+                *other_body_id != body_id
+                    && other_body.body_type == self.body_type
+                    && !removed_bodies.contains(other_body_id)
+            })
+            .filter_map(|(other_body_id, _)| {
+                bodies.get_key_value(other_body_id)
+            })
+            .filter(|(_, other_body)| {
+                other_body.energy
+                    > other_body.genome.division_threshold
+                    && self.pos.distance(other_body.pos)
+                        <= vision_distance
+                    && self.can_see(other_body.pos)
+            })
+            .min_by(|(_, a), (_, b)| {
+                self.pos
+                    .distance(a.pos)
+                    .total_cmp(&self.pos.distance(b.pos))
+            })
+            .map(|(partner_id, partner)| {
+                (*partner_id, partner.clone())
+            })
+        else {
+            return false;
+        };
+
+        if self.pos.distance(partner.pos) > unsafe { MATING_DISTANCE }
+        {
+            let pos_deviation = (partner.pos - self.pos)
+                .normalize_or_zero()
+                * self.speed;
+
+            self.set_status(
+                Status::Walking(pos_deviation),
+                body_id,
+                cells,
+                bodies,
+                crosses,
+                plants,
+            );
+
+            self.move_with(pos_deviation);
+            self.wrap(area_size);
+
+            return true;
+        }
+
+        // The children get the viruses of both parents, the least healed ones
+        let mut viruses = self.viruses.clone();
+        for (virus, energy_spent_for_healing) in &partner.viruses {
+            viruses
+                .entry(*virus)
+                .and_modify(|energy| {
+                    *energy = energy.min(*energy_spent_for_healing)
+                })
+                .or_insert(*energy_spent_for_healing);
+        }
+
+        // Both parents give their part of the energy to the litter
+        let litter_size = ((self.genome.litter_size
+            + partner.genome.litter_size)
+            as f32
+            / 2.0)
+            .round() as usize;
+        let child_energy = Body::get_child_energy(
+            self.get_litter_energy() + partner.get_litter_energy(),
+            litter_size,
+        );
+
+        // Not worth it if the children would die at once
+        if child_energy == 0.0 {
+            return false;
+        }
+
+        // The body stays where it is to have the litter
+        self.velocity = Vec2::ZERO;
+        self.take_litter_energy(body_id, removed_bodies);
+        bodies
+            .get_mut(&partner_id)
+            .unwrap()
+            .take_litter_energy(&partner_id, removed_bodies);
+
+        for _ in 0..litter_size {
+            new_bodies.insert(
+                Instant::now(),
+                Body::new(
+                    self.pos,
+                    Some(child_energy),
+                    self.genome
+                        .crossover(&partner.genome, rng)
+                        .mutate(rng),
+                    self.body_type,
+                    Some(viruses.clone()),
+                    Lineage::child_of(body_id, &self.lineage),
+                    rng,
+                ),
+            );
+        }
+
+        true
+    }
+
+    #[inline(always)]
+    pub fn get_spent_energy(&self, time: f32) -> f32 {
+        time * self.get_movement_energy_cost()
+            + unsafe { ENERGY_SPENT_CONST_FOR_MASS } * self.energy
+            + self.get_skills_energy_cost()
+            + self.get_vision_energy_cost()
+    }
+
+    #[inline(always)]
+    /// The energy spent on moving for one tick on plain terrain. Bigger bodies spend more.
+    pub fn get_movement_energy_cost(&self) -> f32 {
+        self.get_speed().powi(2)
+            * self.energy
+            * self.genome.size
+            * unsafe { ENERGY_SPENT_CONST_FOR_MOVEMENT }
+    }
+
+    #[inline(always)]
+    /// The radius the body is drawn with.
+    pub fn get_radius(&self) -> f32 {
+        OBJECT_RADIUS * self.genome.size
+    }
+
+    #[inline(always)]
+    /// The maximum energy the body can store.
+    pub fn get_max_energy(&self) -> f32 {
+        self.genome.size.powi(2) * unsafe { MAX_ENERGY }
+    }
+
+    #[inline(always)]
+    /// How close food has to be for the body to eat it. Bigger bodies reach farther.
+    pub fn get_eating_distance(&self) -> f32 {
+        (self.get_speed() + self.get_radius() - OBJECT_RADIUS)
+            .max(self.get_speed())
+    }
+
+    #[inline(always)]
+    /// Whether `other_body` is small enough for the body to eat it.
+    pub fn can_swallow(&self, other_body: &Body) -> bool {
+        other_body.genome.size
+            <= self.genome.size * unsafe { MAX_PREY_SIZE_RATIO }
+    }
+
+    #[inline(always)]
+    /// The energy constantly spent on all the skills of the body.
+    pub fn get_skills_energy_cost(&self) -> f32 {
+        self.genome
+            .skills
+            .iter()
+            .map(|skill| skill.energy_cost())
+            .sum()
+    }
+
+    #[inline(always)]
+    /// Whether the food filter skills let the body go for the food.
+    pub fn handle_food_filter_skills(
+        &self,
+        body_id: &BodyId,
+        food: Food,
+    ) -> bool {
+        Skill::ALL
+            .iter()
+            .filter(|skill| skill.has_hook(SkillHook::FoodFilter))
+            .all(|skill| skill.filter_food(self, body_id, food))
+    }
+
+    #[inline(always)]
+    /// Let the chase reaction skills of the body narrow down the chasers.
+    pub fn handle_chase_reaction_skills(
+        &self,
+        chasers: &mut HashMap<BodyId, Self>,
+    ) {
+        for skill in &self.genome.skills {
+            if skill.has_hook(SkillHook::ChaseReaction) {
+                skill.react_to_chasers(self, chasers);
+            }
+        }
+    }
+
+    #[inline(always)]
+    /// Get the direction the chase reaction skills of the body make it escape in. `None` means
+    /// the body simply runs away from the closest chaser.
+    pub fn handle_escape_skills(
+        &self,
+        chasers: &HashMap<BodyId, Self>,
+        cells: &Cells,
+        plants: &HashMap<Cell, HashMap<PlantId, Plant>>,
+    ) -> Option<Vec2> {
+        self.genome
+            .skills
+            .iter()
+            .filter(|skill| skill.has_hook(SkillHook::ChaseReaction))
+            .find_map(|skill| {
+                skill.escape_direction(self, chasers, cells, plants)
+            })
+    }
+
+    #[inline(always)]
+    /// Let the sight skills of the body take in the food it sees.
+    pub fn handle_sight_skills(
+        &mut self,
+        visible_food: impl Iterator<Item = (Vec2, f32)> + Clone,
+    ) {
+        for skill in Skill::ALL {
+            if skill.has_hook(SkillHook::Sight)
+                && self.genome.skills.contains(&skill)
+            {
+                skill.see_food(self, visible_food.clone());
+            }
+        }
+    }
+
+    #[inline(always)]
+    /// Let the tick skills of the body update what it knows about its surroundings.
+    pub fn handle_tick_skills(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+    ) {
+        for skill in Skill::ALL {
+            if skill.has_hook(SkillHook::Tick)
+                && self.genome.skills.contains(&skill)
+            {
+                skill.update(self, body_id, cells, body_cells);
+            }
+        }
+    }
+
+    #[inline(always)]
+    /// Whether `other_body` is a relative with `Skill::PackHunter` chasing the same body as the
+    /// body.
+    pub fn is_in_pack(
+        &self,
+        body_id: &BodyId,
+        other_body_id: &BodyId,
+        other_body: &Self,
+    ) -> bool {
+        other_body_id != body_id
+            && other_body.body_type == self.body_type
+            && other_body.genome.skills.contains(&Skill::PackHunter)
+    }
+
+    #[inline(always)]
+    /// Get the energy the body attacks `other_body` with.
+    pub fn get_attack_energy(
+        &self,
+        body_id: &BodyId,
+        other_body: &Self,
+    ) -> f32 {
+        self.genome
+            .skills
+            .iter()
+            .filter(|skill| skill.has_hook(SkillHook::Catch))
+            .find_map(|skill| {
+                skill.attack_energy(self, body_id, other_body)
+            })
+            .unwrap_or(self.energy)
+    }
+
+    #[inline(always)]
+    /// Get the energy the body attacks `other_body` with together with the rest of the pack
+    /// chasing `other_body`.
+    pub fn get_pack_attack_energy(
+        &self,
+        body_id: &BodyId,
+        other_body: &Self,
+    ) -> f32 {
+        self.energy
+            + other_body
+                .followed_by
+                .iter()
+                .filter(|(chaser_id, chaser)| {
+                    self.is_in_pack(body_id, chaser_id, chaser)
+                })
+                .map(|(_, chaser)| chaser.energy)
+                .sum::<f32>()
+    }
+
+    #[inline(always)]
+    /// Get the part of the energy of the caught body the body gets.
+    pub fn get_caught_body_energy(
+        &self,
+        body_id: &BodyId,
+        caught_body_id: &BodyId,
+        bodies: &mut HashMap<BodyId, Self>,
+        removed_bodies: &HashSet<BodyId>,
+    ) -> f32 {
+        for skill in &self.genome.skills {
+            if skill.has_hook(SkillHook::Catch) {
+                if let Some(energy) = skill.share_caught_body(
+                    self,
+                    body_id,
+                    caught_body_id,
+                    bodies,
+                    removed_bodies,
+                ) {
+                    return energy;
+                }
+            }
+        }
+
+        bodies[caught_body_id].energy
+    }
+
+    #[inline(always)]
+    /// Returns whether the body waits for the prey instead of going for the food.
+    pub fn handle_wait_for_prey_skills(
+        &mut self,
+        food: &FoodInfo,
+    ) -> bool {
+        for skill in Skill::ALL {
+            if skill.has_hook(SkillHook::Catch)
+                && self.genome.skills.contains(&skill)
+                && skill.wait_for_prey(self, food)
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    #[inline(always)]
+    /// Split the energy of the caught body among the pack members close to it and get the
+    /// part the body gets.
+    pub fn share_caught_body(
+        &self,
+        body_id: &BodyId,
+        caught_body_id: &BodyId,
+        bodies: &mut HashMap<BodyId, Self>,
+        removed_bodies: &HashSet<BodyId>,
+    ) -> f32 {
+        let caught_body = bodies.get(caught_body_id).unwrap();
+
+        let pack = caught_body
+            .followed_by
+            .iter()
+            .filter(|(chaser_id, chaser)| {
+                self.is_in_pack(body_id, chaser_id, chaser)
+                    && !removed_bodies.contains(chaser_id)
+            })
+            .map(|(chaser_id, _)| *chaser_id)
+            .filter(|chaser_id| {
+                // The copies in `followed_by` may be outdated
+                bodies.get(chaser_id).is_some_and(|chaser| {
+                    chaser.pos.distance(caught_body.pos)
+                        <= unsafe { PACK_SHARE_DISTANCE }
+                })
+            })
+            .collect::<Vec<_>>();
+        let share = caught_body.energy / (pack.len() + 1) as f32;
+        let viruses = caught_body.viruses.clone();
+
+        for chaser_id in &pack {
+            let chaser = bodies.get_mut(chaser_id).unwrap();
+            // The rest of the energy is lost
+            chaser.energy =
+                (chaser.energy + share).min(chaser.get_max_energy());
+            chaser.get_viruses(&viruses);
+        }
+
+        share
+    }
+
+    #[inline(always)]
+    /// Count the relatives close enough to the body to make a herd with it.
+    pub fn update_herd_size(
+        &mut self,
+        body_id: &BodyId,
+        cells: &Cells,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+    ) {
+        self.herd_size = cells
+            .get_cells_within(&self.pos, unsafe { HERD_DISTANCE })
+            .flat_map(|cell| body_cells.get(&cell).unwrap())
+            .filter(|(other_body_id, other_body)| {
+                *other_body_id != body_id
+                    && other_body.body_type == self.body_type
+                    && self.pos.distance(other_body.pos)
+                        <= unsafe { HERD_DISTANCE }
+            })
+            .count();
+    }
+
+    /// Put every body into the cell it's in now.
+    pub fn update_body_cells(
+        body_cells: &mut HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+        bodies: &HashMap<BodyId, Self>,
+        cells: &Cells,
+    ) {
+        for cell in body_cells.values_mut() {
+            cell.clear();
+        }
+
+        for (body_id, body) in bodies {
+            body_cells
+                .get_mut(&cells.get_cell_by_pos(&body.pos))
+                .unwrap()
+                .insert(
+                    *body_id,
+                    BodyPosition {
+                        pos:       body.pos,
+                        body_type: body.body_type,
+                    },
+                );
+        }
+    }
+
+    #[inline(always)]
+    /// How many times longer chasing the body seems to take because of its herd.
+    pub fn get_herd_protection(&self) -> f32 {
+        1.0 + unsafe { HERD_PROTECTION } * self.herd_size as f32
+    }
+
+    #[inline(always)]
+    /// Remember where the food is, forgetting the oldest memories if there's no room left.
+    pub fn remember_food(
+        &mut self,
+        food: impl Iterator<Item = (Vec2, f32)>,
+    ) {
+        for (pos, energy) in food {
+            self.memory.retain(|remembered_food| {
+                remembered_food.pos.distance(pos) > OBJECT_RADIUS
+            });
+
+            if self.memory.len() >= unsafe { MEMORY_SIZE } {
+                self.memory.remove(0);
+            }
+
+            self.memory.push(RememberedFood {
+                pos,
+                energy,
+                timestamp: Instant::now(),
+            });
+        }
+    }
+
+    #[inline(always)]
+    /// Get the point the body aims at when chasing `other_body`.
+    pub fn handle_pursuit_skills(&self, other_body: &Self) -> Vec2 {
+        self.genome
+            .skills
+            .iter()
+            .filter(|skill| skill.has_hook(SkillHook::Pursuit))
+            .find_map(|skill| skill.aim(self, other_body))
+            .unwrap_or(other_body.pos)
+    }
+
+    #[inline(always)]
+    /// Get the point where the body meets `other_body` if `other_body` keeps moving with its
+    /// current velocity, and the time it takes to get there.
+    pub fn get_interception(
+        &self,
+        other_body: &Self,
+    ) -> Option<(Vec2, f32)> {
+        let velocity = other_body.velocity;
+        let relative_pos = other_body.pos - self.pos;
+
+        // |relative_pos + velocity * t| = speed * t
+        let a = velocity.length_squared() - self.get_speed().powi(2);
+        let b = 2.0 * relative_pos.dot(velocity);
+        let c = relative_pos.length_squared();
+
+        let time = if a.abs() < f32::EPSILON {
+            if b >= 0.0 {
+                return None;
+            }
+
+            -c / b
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+
+            if discriminant < 0.0 {
+                return None;
+            }
+
+            let discriminant_sqrt = discriminant.sqrt();
+
+            [
+                (-b - discriminant_sqrt) / (2.0 * a),
+                (-b + discriminant_sqrt) / (2.0 * a),
+            ]
+            .into_iter()
+            .filter(|time| *time > 0.0)
+            .reduce(f32::min)?
+        };
+
+        Some((other_body.pos + velocity * time, time))
+    }
+
+    #[inline(always)]
+    /// Get the time it takes the body to catch `other_body`.
+    pub fn get_time_to_catch(
+        &self,
+        other_body: &Self,
+    ) -> Option<f32> {
+        if self.genome.skills.contains(&Skill::Intercept) {
+            self.get_interception(other_body).map(|(_, time)| time)
+        } else {
+            let delta = self.get_speed() - other_body.get_speed();
+
+            if delta <= 0.0 {
+                return None;
+            }
+
+            Some(self.pos.distance(other_body.pos) / delta)
+        }
+    }
+
+    /// Generate a random position until it suits certain creteria.
+    pub fn randomly_spawn_body(
+        bodies: &mut HashMap<Instant, Self>,
+        area_size: &Vec2,
+        eating_strategy: EatingStrategy,
+        body_type: usize,
+        rng: &mut StdRng,
+    ) {
+        let mut pos = Vec2::default();
+
+        // Make sure the position is far enough from the rest of the bodies and the borders of the area
+        while {
+            pos.x = rng.gen_range(0.0..area_size.x);
+            pos.y = rng.gen_range(0.0..area_size.y);
+            (pos.x <= OBJECT_RADIUS + MIN_GAP
+                || pos.x >= area_size.x - OBJECT_RADIUS - MIN_GAP)
+                || (pos.y <= OBJECT_RADIUS + MIN_GAP
+                    || pos.y >= area_size.y - OBJECT_RADIUS - MIN_GAP)
+                || bodies.values().any(|body| {
+                    body.pos.distance(pos)
+                        < OBJECT_RADIUS * 2.0 + MIN_GAP
+                })
+                || terrain_map().is_blocked(&pos)
+        } {}
+
+        bodies.insert(
+            Instant::now(),
+            Body::new(
+                pos,
+                None,
+                Genome::new(
+                    eating_strategy,
+                    Body::get_distinct_color(
+                        &bodies
+                            .values()
+                            .map(|body| body.genome.color)
+                            .collect::<Vec<_>>(),
+                        unsafe { BODIES_N },
+                    ),
+                    rng,
+                ),
+                body_type as u16,
+                None,
+                Lineage::FOUNDER,
+                rng,
+            ),
+        );
+    }
+
+    /// Get a color different enough from the colors of the body types, the plant kinds and
+    /// the virus mark.
+    pub fn get_distinct_color(
+        body_type_colors: &[Color],
+        body_types_n: usize,
+    ) -> Color {
+        let taken_colors = body_type_colors
+            .iter()
+            .copied()
+            .chain(plant_kinds().iter().map(|kind| kind.color))
+            .chain([RED])
+            .map(|color| Vec3 {
+                x: color.r,
+                y: color.g,
+                z: color.b,
+            })
+            .collect::<Vec<_>>();
+
+        let real_color_gap = COLOR_GAP
+            / ((body_types_n + plant_kinds().len() + 1) as f32)
+                .powf(1.0 / 3.0);
+
+        let mut color = Color::default();
+
+        // Give up on the gap after a number of attempts so that it can't loop forever
+        for _ in 0..DISTINCT_COLOR_ATTEMPTS_N {
+            color = Color::from_rgba(
+                gen_range(COLOR_MIN, COLOR_MAX),
+                gen_range(COLOR_MIN, COLOR_MAX),
+                gen_range(COLOR_MIN, COLOR_MAX),
+                255,
+            );
+
+            let rgb = Vec3 {
+                x: color.r,
+                y: color.g,
+                z: color.b,
+            };
+
+            if taken_colors.iter().all(|taken_rgb| {
+                taken_rgb.distance(rgb) >= real_color_gap
+            }) {
+                break;
+            }
+        }
+
+        color
+    }
+
+    #[inline(always)]
+    /// Turn the body into the founder of a new body type if its genome has drifted too far
+    /// from the genome its body type started with.
+    pub fn handle_speciation(
+        &mut self,
+        founders: &mut HashMap<u16, Genome>,
+        next_body_type: &mut u16,
+    ) {
+        if !unsafe { SPECIATION }
+            || self.genome.distance(&founders[&self.body_type])
+                <= unsafe { SPECIATION_DISTANCE }
+        {
+            return;
+        }
+
+        let body_type_colors = founders
+            .values()
+            .map(|genome| genome.color)
+            .collect::<Vec<_>>();
+
+        self.genome.color = Body::get_distinct_color(
+            &body_type_colors,
+            body_type_colors.len() + 1,
+        );
+        self.body_type = *next_body_type;
+        founders.insert(self.body_type, self.genome.clone());
+
+        *next_body_type += 1;
+    }
+
+    pub fn set_status(
+        &mut self,
+        status: Status,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Self>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+    ) {
+        Body::followed_by_cleanup(
+            &body_id, &cells, bodies, crosses, plants, None,
+        );
+        self.status = status;
+    }
+
+    #[inline(always)]
+    /// How much the body wants to go for the food.
+    pub fn get_food_score(&self, food: &FoodInfo) -> f32 {
+        let new_viruses_n = food.viruses.map_or(0, |viruses| {
+            viruses
+                .keys()
+                .filter(|virus| !self.viruses.contains_key(virus))
+                .count()
+        });
+
+        let food_type_preference = match food.food_type {
+            ObjectType::Body => self.genome.preferences.body,
+            ObjectType::Cross => self.genome.preferences.cross,
+            ObjectType::Plant => self.genome.preferences.plant,
+        };
+
+        food_type_preference
+            + self.genome.preferences.energy * food.energy
+            - self.genome.preferences.distance
+                * self.pos.distance(food.pos)
+            - self.genome.preferences.infection * new_viruses_n as f32
+    }
+
+    #[inline(always)]
+    pub fn handle_profitable_when_arrived_body(
+        &self,
+        other_body: &Body,
+    ) -> bool {
+        if self.genome.skills.contains(&Skill::ProfitableWhenArrived)
+        {
+            match self.get_time_to_catch(other_body) {
+                Some(time) => {
+                    self.get_spent_energy(
+                        time * other_body.get_herd_protection(),
+                    ) < other_body.energy
+                }
+                None => false,
+            }
+        } else {
+            true
+        }
+    }
+
+    #[inline(always)]
+    pub fn handle_profitable_when_arrived_plant(
+        &self,
+        plant: &Plant,
+    ) -> bool {
+        if self.genome.skills.contains(&Skill::ProfitableWhenArrived)
+        {
+            self.get_spent_energy(
+                self.pos.distance(plant.pos) / self.speed,
+            ) < plant.get_contained_energy()
+        } else {
+            true
+        }
+    }
+
+    #[inline(always)]
+    pub fn handle_profitable_when_arrived_cross(
         &self,
         cross: &Cross,
     ) -> bool {
-        if self.skills.contains(&Skill::ProfitableWhenArrived) {
+        if self.genome.skills.contains(&Skill::ProfitableWhenArrived)
+        {
             self.get_spent_energy(
                 self.pos.distance(cross.pos) / self.speed,
             ) < cross.energy
@@ -920,7 +2107,7 @@ impl Body {
         &self,
         cross: &Cross,
     ) -> bool {
-        if self.skills.contains(&Skill::AliveWhenArrived) {
+        if self.genome.skills.contains(&Skill::AliveWhenArrived) {
             self.energy
                 - self.get_spent_energy(
                     self.pos.distance(cross.pos) / self.speed,
@@ -936,18 +2123,17 @@ impl Body {
         &self,
         other_body: &Self,
     ) -> bool {
-        if self.skills.contains(&Skill::AliveWhenArrived) {
-            let divisor = self.speed - other_body.speed;
-
-            if divisor <= 0.0 {
-                return false;
+        if self.genome.skills.contains(&Skill::AliveWhenArrived) {
+            match self.get_time_to_catch(other_body) {
+                Some(time) => {
+                    self.energy
+                        - self.get_spent_energy(
+                            time * other_body.get_herd_protection(),
+                        )
+                        > unsafe { MIN_ENERGY }
+                }
+                None => false,
             }
-
-            self.energy
-                - self.get_spent_energy(
-                    self.pos.distance(other_body.pos) / divisor,
-                )
-                > unsafe { MIN_ENERGY }
         } else {
             true
         }
@@ -958,7 +2144,7 @@ impl Body {
         &self,
         plant: &Plant,
     ) -> bool {
-        if self.skills.contains(&Skill::AliveWhenArrived) {
+        if self.genome.skills.contains(&Skill::AliveWhenArrived) {
             self.energy
                 - self.get_spent_energy(
                     self.pos.distance(plant.pos) / self.speed,
@@ -974,7 +2160,7 @@ impl Body {
         &self,
         cross: &Cross,
     ) -> bool {
-        if self.skills.contains(&Skill::AvoidNewViruses) {
+        if self.genome.skills.contains(&Skill::AvoidNewViruses) {
             cross
                 .viruses
                 .keys()
@@ -984,12 +2170,32 @@ impl Body {
         }
     }
 
+    #[inline(always)]
+    pub fn handle_detect_poison(&self, plant: &Plant) -> bool {
+        if self.genome.skills.contains(&Skill::DetectPoison) {
+            !plant.get_kind().is_poisonous()
+        } else {
+            true
+        }
+    }
+
+    #[inline(always)]
+    /// Suffer from the poison of the plant the body has eaten.
+    pub fn get_poisoned(&mut self, plant: &Plant) {
+        let kind = plant.get_kind();
+
+        self.energy =
+            (self.energy - kind.poison_energy_loss).max(0.0);
+        self.lifespan =
+            (self.lifespan - kind.poison_lifespan_loss).max(0.0);
+    }
+
     #[inline(always)]
     pub fn handle_avoid_new_viruses_body(
         &self,
         other_body: &Self,
     ) -> bool {
-        if self.skills.contains(&Skill::AvoidNewViruses) {
+        if self.genome.skills.contains(&Skill::AvoidNewViruses) {
             other_body
                 .viruses
                 .keys()
@@ -1005,7 +2211,11 @@ impl Body {
         body_id: &BodyId,
         followed_by: &HashMap<BodyId, Self>,
     ) -> bool {
-        if self.skills.contains(&Skill::DoNotCompeteWithRelatives) {
+        if self
+            .genome
+            .skills
+            .contains(&Skill::DoNotCompeteWithRelatives)
+        {
             followed_by.iter().all(|(other_body_id, other_body)| {
                 other_body_id == body_id
                     || other_body.body_type != self.body_type
@@ -1020,7 +2230,7 @@ impl Body {
         body_id: &BodyId,
         cross: &Cross,
     ) -> bool {
-        if self.skills.contains(&Skill::WillArriveFirst) {
+        if self.genome.skills.contains(&Skill::WillArriveFirst) {
             let time = self.pos.distance(cross.pos) / self.speed;
 
             cross.followed_by.iter().all(|(chaser_id, chaser)| {
@@ -1040,24 +2250,20 @@ impl Body {
         body_id: &BodyId,
         other_body: &Self,
     ) -> bool {
-        if self.skills.contains(&Skill::WillArriveFirst) {
-            let delta = self.speed - other_body.speed;
-            if delta <= 0.0 {
+        if self.genome.skills.contains(&Skill::WillArriveFirst) {
+            let Some(time) = self.get_time_to_catch(other_body)
+            else {
                 return false;
-            }
+            };
 
-            let time = self.pos.distance(other_body.pos) / delta;
             other_body.followed_by.iter().all(
                 |(chaser_id, chaser)| {
-                    chaser_id == body_id || {
-                        let chaser_delta =
-                            chaser.speed - other_body.speed;
-
-                        chaser_delta > 0.0
-                            && time
-                                < chaser.pos.distance(other_body.pos)
-                                    / chaser_delta
-                    }
+                    chaser_id == body_id
+                        || chaser
+                            .get_time_to_catch(other_body)
+                            .is_some_and(|chaser_time| {
+                                time < chaser_time
+                            })
                 },
             )
         } else {
@@ -1071,7 +2277,7 @@ impl Body {
         body_id: &BodyId,
         plant: &Plant,
     ) -> bool {
-        if self.skills.contains(&Skill::WillArriveFirst) {
+        if self.genome.skills.contains(&Skill::WillArriveFirst) {
             let time = self.pos.distance(plant.pos) / self.speed;
 
             plant.followed_by.iter().all(|(chaser_id, chaser)| {
@@ -1091,7 +2297,7 @@ impl Body {
         cross: &Cross,
     ) -> bool {
         self.body_type != cross.body_type
-            || self.skills.contains(&Skill::EatCrossesOfMyType)
+            || self.genome.skills.contains(&Skill::EatCrossesOfMyType)
     }
 
     #[inline(always)]
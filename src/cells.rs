@@ -24,4 +24,30 @@ impl Cells {
             j: (pos.x / self.cell_width) as usize,
         }
     }
+
+    #[inline(always)]
+    /// The cells the circle around `pos` may overlap with.
+    pub fn get_cells_within(
+        &self,
+        pos: &Vec2,
+        radius: f32,
+    ) -> impl Iterator<Item = Cell> {
+        let i_min = ((pos.y - radius) / self.cell_height)
+            .floor()
+            .max(0.0) as usize;
+        let i_max = ((pos.y + radius) / self.cell_height)
+            .floor()
+            .min(self.rows as f32 - 1.0) as usize;
+        let j_min = ((pos.x - radius) / self.cell_width)
+            .floor()
+            .max(0.0) as usize;
+        let j_max = ((pos.x + radius) / self.cell_width)
+            .floor()
+            .min(self.columns as f32 - 1.0)
+            as usize;
+
+        (i_min..=i_max).flat_map(move |i| {
+            (j_min..=j_max).map(move |j| Cell { i, j })
+        })
+    }
 }
@@ -1,25 +1,78 @@
-use crate::{CONDITION_CHANCE, CONDITION_LIFETIME};
-use rand::{prelude::IteratorRandom, rngs::StdRng, Rng};
-use std::time::{Duration, Instant};
+use crate::{constants::*, user_constants::*, Cell, Cells};
+use macroquad::prelude::{draw_circle, Color, Vec2};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng};
+use std::{
+    f32::consts::PI,
+    time::{Duration, Instant},
+};
 
 #[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
 pub enum Condition {
+    /// Plants die more often.
     Drought,
+    /// More plants spawn.
     Rain,
+    /// Bodies spend more energy and move slower.
+    Winter,
+    /// Bodies lose their lifespan faster.
+    Heatwave,
+    /// Bodies see less far.
+    Fog,
+    /// Bodies randomly get infected with a virus.
+    Plague,
 }
 
 impl Condition {
-    pub const ALL: [Self; 2] = [Self::Drought, Self::Rain];
+    pub const ALL: [Self; 6] = [
+        Self::Drought,
+        Self::Rain,
+        Self::Winter,
+        Self::Heatwave,
+        Self::Fog,
+        Self::Plague,
+    ];
+
+    #[inline(always)]
+    pub fn get_color(self) -> Color {
+        match self {
+            Self::Drought => DROUGHT_COLOR,
+            Self::Rain => RAIN_COLOR,
+            Self::Winter => WINTER_COLOR,
+            Self::Heatwave => HEATWAVE_COLOR,
+            Self::Fog => FOG_COLOR,
+            Self::Plague => PLAGUE_COLOR,
+        }
+    }
+
+    #[inline(always)]
+    /// How likely the condition is to be the one that starts, relative to the others.
+    pub fn get_chance(self) -> f32 {
+        match self {
+            Self::Drought => unsafe { DROUGHT_CHANCE },
+            Self::Rain => unsafe { RAIN_CHANCE },
+            Self::Winter => unsafe { WINTER_CHANCE },
+            Self::Heatwave => unsafe { HEATWAVE_CHANCE },
+            Self::Fog => unsafe { FOG_CHANCE },
+            Self::Plague => unsafe { PLAGUE_CHANCE },
+        }
+    }
 
     #[inline(always)]
     pub fn update_condition(
-        condition: &mut Option<(Condition, (Instant, Duration))>,
+        condition: &mut Option<ActiveCondition>,
+        area_size: &Vec2,
         rng: &mut StdRng,
     ) {
         match condition {
-            Some((_, (timestamp, lifetime))) => {
-                if &timestamp.elapsed() > lifetime {
+            Some(active_condition) => {
+                if active_condition.timestamp.elapsed()
+                    > active_condition.lifetime
+                {
                     *condition = None;
+                } else if let Some(region) =
+                    &mut active_condition.region
+                {
+                    region.drift(area_size);
                 }
             }
             None => {
@@ -27,17 +80,144 @@ impl Condition {
                     || rng.gen_range(0.0..1.0)
                         <= unsafe { CONDITION_CHANCE }
                 {
-                    *condition = Some((
-                        *Condition::ALL.iter().choose(rng).unwrap(),
-                        (
-                            Instant::now(),
-                            Duration::from_secs(rng.gen_range(
-                                unsafe { CONDITION_LIFETIME.clone() },
-                            )),
+                    *condition = Some(ActiveCondition {
+                        condition: *Condition::ALL
+                            .choose_weighted(rng, |condition| {
+                                condition.get_chance()
+                            })
+                            .unwrap(),
+                        timestamp: Instant::now(),
+                        lifetime:  Duration::from_secs(
+                            rng.gen_range(unsafe {
+                                CONDITION_LIFETIME.clone()
+                            }),
                         ),
-                    ));
+                        region:    if unsafe { REGIONAL_CONDITIONS } {
+                            Some(Region::new(area_size, rng))
+                        } else {
+                            None
+                        },
+                    });
                 }
             }
         }
     }
 }
+
+/// A circle a condition covers, which drifts across the area.
+#[derive(Clone, Copy)]
+pub struct Region {
+    pub center:   Vec2,
+    pub radius:   f32,
+    pub velocity: Vec2,
+}
+
+impl Region {
+    pub fn new(area_size: &Vec2, rng: &mut StdRng) -> Self {
+        let [min_radius, max_radius] = unsafe { CONDITION_RADIUS };
+
+        Self {
+            center:   Vec2::new(
+                rng.gen_range(0.0..area_size.x),
+                rng.gen_range(0.0..area_size.y),
+            ),
+            radius:   rng.gen_range(min_radius..=max_radius),
+            velocity: Vec2::from_angle(rng.gen_range(0.0..2.0 * PI))
+                * unsafe { CONDITION_DRIFT_SPEED },
+        }
+    }
+
+    #[inline(always)]
+    /// Move the region and bounce it off the borders of the area.
+    pub fn drift(&mut self, area_size: &Vec2) {
+        self.center += self.velocity;
+
+        if self.center.x <= 0.0 || self.center.x >= area_size.x {
+            self.velocity.x = -self.velocity.x;
+            self.center.x = self.center.x.clamp(0.0, area_size.x);
+        }
+
+        if self.center.y <= 0.0 || self.center.y >= area_size.y {
+            self.velocity.y = -self.velocity.y;
+            self.center.y = self.center.y.clamp(0.0, area_size.y);
+        }
+    }
+
+    #[inline(always)]
+    pub fn contains(&self, pos: &Vec2) -> bool {
+        self.center.distance(*pos) <= self.radius
+    }
+
+    #[inline(always)]
+    /// The part of the area the region covers, assuming it's fully within the area.
+    pub fn get_coverage(&self, area_size: &Vec2) -> f32 {
+        (PI * self.radius.powi(2) / (area_size.x * area_size.y))
+            .min(1.0)
+    }
+
+    #[inline(always)]
+    pub fn get_random_pos(&self, rng: &mut StdRng) -> Vec2 {
+        // The square root makes the positions spread evenly
+        self.center
+            + Vec2::from_angle(rng.gen_range(0.0..2.0 * PI))
+                * self.radius
+                * rng.gen_range(0.0f32..1.0).sqrt()
+    }
+
+    /// The cells the region may overlap with.
+    pub fn get_cells(&self, cells: &Cells) -> Vec<Cell> {
+        let i_min = ((self.center.y - self.radius)
+            / cells.cell_height)
+            .floor()
+            .max(0.0) as usize;
+        let i_max =
+            ((self.center.y + self.radius) / cells.cell_height)
+                .floor()
+                .min(cells.rows as f32 - 1.0) as usize;
+        let j_min = ((self.center.x - self.radius) / cells.cell_width)
+            .floor()
+            .max(0.0) as usize;
+        let j_max = ((self.center.x + self.radius) / cells.cell_width)
+            .floor()
+            .min(cells.columns as f32 - 1.0)
+            as usize;
+
+        let mut region_cells = Vec::new();
+        for i in i_min..=i_max {
+            for j in j_min..=j_max {
+                region_cells.push(Cell { i, j });
+            }
+        }
+
+        region_cells
+    }
+}
+
+/// The condition that is on now.
+#[derive(Clone, Copy)]
+pub struct ActiveCondition {
+    pub condition: Condition,
+    pub timestamp: Instant,
+    pub lifetime:  Duration,
+    /// `None` if the condition covers the whole area.
+    pub region:    Option<Region>,
+}
+
+impl ActiveCondition {
+    #[inline(always)]
+    pub fn covers(&self, pos: &Vec2) -> bool {
+        self.region.is_none_or(|region| region.contains(pos))
+    }
+
+    #[inline(always)]
+    pub fn draw(&self) {
+        if let Some(region) = self.region {
+            draw_circle(
+                region.center.x,
+                region.center.y,
+                region.radius,
+                self.condition.get_color(),
+            );
+        }
+    }
+}
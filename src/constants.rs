@@ -1,3 +1,4 @@
+use macroquad::color::Color;
 use std::f32::consts::SQRT_3;
 
 pub const DEFAULT_SCREEN_WIDTH: f32 = 1920.0;
@@ -6,6 +7,23 @@ pub const DEFAULT_SCREEN_HEIGHT: f32 = 1080.0;
 // TOML
 pub const CONFIG_FILE_NAME: &str = "config.toml";
 
+// Report
+pub const STRATEGIES_REPORT_FILE_NAME: &str = "strategies.csv";
+pub const GENOMES_FILE_NAME: &str = "genomes.toml";
+pub const LINEAGE_NEWICK_FILE_NAME: &str = "lineage.nwk";
+pub const LINEAGE_JSON_FILE_NAME: &str = "lineage.json";
+
+// Fertility
+pub const FERTILITY_IMAGE_FILE_NAME: &str = "fertility.png";
+/// The probability for a plant in a fully fertile cell to avoid dying.
+pub const FERTILITY_DEATH_PROTECTION: f32 = 0.5;
+
+// Terrain
+pub const TERRAIN_IMAGE_FILE_NAME: &str = "terrain.png";
+pub const WATER_COLOR: Color = Color::new(0.1, 0.3, 0.8, 0.35);
+pub const MUD_COLOR: Color = Color::new(0.45, 0.3, 0.15, 0.35);
+pub const ROCK_COLOR: Color = Color::new(0.5, 0.5, 0.5, 0.8);
+
 // Base
 pub const DEFAULT_CELL_ROWS: usize = 129; // May be needed to be changed when the perfect values for the
                                           // evolution process have been determined
@@ -20,24 +38,34 @@ pub static DEFAULT_AREA_SIZE_RATIO: f32 =
     DEFAULT_SCREEN_WIDTH / DEFAULT_SCREEN_HEIGHT;
 
 pub const OBJECT_RADIUS: f32 = 10.0;
+/// How many lines the arc of a vision cone is drawn with.
+pub const VISION_CONE_SEGMENTS_N: usize = 16;
+/// How fast a body standing still looks around, in radians per tick.
+pub const IDLE_TURNING_RATE: f32 = 0.02;
 /// Used for `get_with_deviation`.
 pub const COLOR_MIN: u8 = 50;
 pub const COLOR_MAX: u8 = 250;
 
 // Evolution process
-pub const GRASS_ENERGY: f32 = 100.0;
-pub const BANANA_ENERGY: f32 = GRASS_ENERGY * 2.0;
-
 pub const MIN_GAP: f32 = 3.0;
 pub const COLOR_GAP: f32 = 0.55; // Depends on COLOR_MIN and COLOR_MAX
+pub const DISTINCT_COLOR_ATTEMPTS_N: usize = 1000;
 pub const PLANT_SPAWN_TIME_LIMIT: u64 = 5; // In millis
+/// The part of the full size a seedling is drawn with.
+pub const SEEDLING_SIZE: f32 = 0.4;
+/// How many random cells are checked for a plant to grow a seed from.
+pub const SEED_PARENT_ATTEMPTS_N: usize = 16;
 
 // Plants
 pub static mut PLANTS_N_FOR_ONE_STEP: usize = 0;
 
 // Conditions
-pub const RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER: f32 = 2.2;
-pub const DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER: f32 = 1.2;
+pub const DROUGHT_COLOR: Color = Color::new(0.9, 0.5, 0.1, 0.15);
+pub const RAIN_COLOR: Color = Color::new(0.2, 0.4, 1.0, 0.15);
+pub const WINTER_COLOR: Color = Color::new(0.9, 0.95, 1.0, 0.2);
+pub const HEATWAVE_COLOR: Color = Color::new(1.0, 0.1, 0.1, 0.15);
+pub const FOG_COLOR: Color = Color::new(0.6, 0.6, 0.6, 0.3);
+pub const PLAGUE_COLOR: Color = Color::new(0.4, 0.8, 0.1, 0.15);
 
 // UI
 pub const EVOLUTION_INFO_FONT_SIZE: u16 = 500;
@@ -37,7 +37,7 @@ impl Cross {
             timestamp:   Instant::now(),
             energy:      body.energy,
             viruses:     body.viruses.clone(),
-            color:       body.color,
+            color:       body.genome.color,
             body_type:   body.body_type,
             followed_by: body.followed_by.clone(),
         }
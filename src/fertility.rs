@@ -0,0 +1,66 @@
+use crate::{
+    constants::*, generate_value_noise, load_map_image,
+    user_constants::*, Cell, Cells, MapSource,
+};
+use rand::{rngs::StdRng, Rng};
+
+/// How fertile every cell is, from 0.0 to 1.0.
+pub struct FertilityMap {
+    /// Indexed by `i * columns + j`; `None` if every cell is fully fertile.
+    values:  Option<Vec<f32>>,
+    columns: usize,
+}
+
+impl FertilityMap {
+    pub fn new(cells: &Cells) -> Self {
+        let values = match unsafe { FERTILITY_MAP_SOURCE } {
+            MapSource::None => None,
+            MapSource::Noise => Some(generate_value_noise(
+                cells,
+                unsafe { FERTILITY_NOISE_SEED },
+                unsafe { FERTILITY_NOISE_SCALE },
+            )),
+            // The image is grayscale, and white is the most fertile
+            MapSource::Image => Some(
+                load_map_image(cells, FERTILITY_IMAGE_FILE_NAME)
+                    .into_iter()
+                    .map(|color| (color.r + color.g + color.b) / 3.0)
+                    .collect(),
+            ),
+        };
+
+        Self {
+            values,
+            columns: cells.columns,
+        }
+    }
+
+    #[inline(always)]
+    pub fn get(&self, cell: &Cell) -> f32 {
+        match &self.values {
+            Some(values) => values[cell.i * self.columns + cell.j],
+            None => 1.0,
+        }
+    }
+
+    #[inline(always)]
+    /// Whether a plant may spawn in the cell. The more fertile the cell is, the more
+    /// likely it is.
+    pub fn allows_spawn(
+        &self,
+        cell: &Cell,
+        rng: &mut StdRng,
+    ) -> bool {
+        self.values.is_none()
+            || rng.gen_range(0.0..1.0) < self.get(cell)
+    }
+
+    #[inline(always)]
+    /// Whether a plant in the cell avoids dying when it's been picked to die. The more
+    /// fertile the cell is, the more likely it is.
+    pub fn protects(&self, cell: &Cell, rng: &mut StdRng) -> bool {
+        self.values.is_some()
+            && rng.gen_range(0.0..1.0)
+                < self.get(cell) * FERTILITY_DEATH_PROTECTION
+    }
+}
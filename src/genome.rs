@@ -0,0 +1,431 @@
+use crate::{
+    get_with_deviation, user_constants::*, EatingStrategy, Skill,
+};
+use macroquad::prelude::Color;
+use rand::{random, rngs::StdRng, seq::IteratorRandom, Rng};
+use serde::Serializer;
+use serde_derive::Serialize;
+use std::collections::HashSet;
+
+/// How a numeric gene mutates. Filled from `config.toml`.
+#[derive(Clone, Copy)]
+pub struct GeneProperties {
+    /// The probability the gene of a child mutates.
+    pub mutation_chance: f32,
+    /// The deviation the gene mutates with.
+    pub deviation:       f32,
+    /// The range the gene always stays within.
+    pub bounds:          (f32, f32),
+}
+
+impl GeneProperties {
+    pub const ZERO: Self = Self {
+        mutation_chance: 0.0,
+        deviation:       0.0,
+        bounds:          (0.0, 0.0),
+    };
+
+    #[inline(always)]
+    /// Get the gene of a child.
+    pub fn mutate(self, value: f32, rng: &mut StdRng) -> f32 {
+        let value = if self.mutation_chance == 1.0
+            || rng.gen_range(0.0..1.0) <= self.mutation_chance
+        {
+            // Negative genes, such as preferences, would reverse the range
+            let part = (value * self.deviation).abs();
+            rng.gen_range(value - part..=value + part)
+        } else {
+            value
+        };
+
+        value.clamp(self.bounds.0, self.bounds.1)
+    }
+}
+
+/// How much a body wants to go for food, depending on the food.
+#[derive(Clone, Copy, PartialEq, Serialize)]
+pub struct Preferences {
+    /// Added to the score of a living body.
+    pub body:      f32,
+    /// Added to the score of a cross.
+    pub cross:     f32,
+    /// Added to the score of a plant.
+    pub plant:     f32,
+    /// Added to the score for every unit of energy the food contains.
+    pub energy:    f32,
+    /// Subtracted from the score for every unit of distance to the food.
+    pub distance:  f32,
+    /// Subtracted from the score for every virus the body would get from the food.
+    pub infection: f32,
+}
+
+impl Preferences {
+    #[inline(always)]
+    /// All the preferences in one order, used for comparing.
+    fn as_array(self) -> [f32; 6] {
+        [
+            self.body,
+            self.cross,
+            self.plant,
+            self.energy,
+            self.distance,
+            self.infection,
+        ]
+    }
+
+    #[inline(always)]
+    /// Apply `f` to every preference.
+    fn map(self, mut f: impl FnMut(f32) -> f32) -> Self {
+        Self {
+            body:      f(self.body),
+            cross:     f(self.cross),
+            plant:     f(self.plant),
+            energy:    f(self.energy),
+            distance:  f(self.distance),
+            infection: f(self.infection),
+        }
+    }
+}
+
+/// Everything a body passes on to its children.
+/// https://github.com/kul-sudo/eportal/blob/main/README.md#genome
+#[derive(Clone, PartialEq, Serialize)]
+pub struct Genome {
+    /// The speed the body has unless it's infected.
+    pub speed:               f32,
+    /// The vision distance the body has unless it's infected.
+    pub vision_distance:     f32,
+    /// How wide the field of view of the body is in radians.
+    pub vision_angle:        f32,
+    pub division_threshold:  f32,
+    /// How big the body is compared to the usual size.
+    pub size:                f32,
+    /// How much the speed of the body can change at once, used with inertia.
+    pub max_acceleration:    f32,
+    /// How much the body can turn at once in radians, used with inertia.
+    pub max_turning_rate:    f32,
+    /// How many children the body has at once.
+    pub litter_size:         usize,
+    /// The part of the energy the body gives to its children.
+    pub litter_energy_share: f32,
+    pub eating_strategy:     EatingStrategy,
+    pub preferences:         Preferences,
+    #[serde(serialize_with = "serialize_skills")]
+    pub skills:              HashSet<Skill>,
+    /// The color of the body type, which is never mutated.
+    #[serde(serialize_with = "serialize_color")]
+    pub color:               Color,
+}
+
+impl Genome {
+    /// The genome of a body of the 1st generation.
+    pub fn new(
+        eating_strategy: EatingStrategy,
+        color: Color,
+        rng: &mut StdRng,
+    ) -> Self {
+        unsafe {
+            Self {
+                speed: get_with_deviation(AVERAGE_SPEED, rng)
+                    .clamp(SPEED_GENE.bounds.0, SPEED_GENE.bounds.1),
+                vision_distance: get_with_deviation(
+                    AVERAGE_VISION_DISTANCE,
+                    rng,
+                )
+                .clamp(
+                    VISION_DISTANCE_GENE.bounds.0,
+                    VISION_DISTANCE_GENE.bounds.1,
+                ),
+                vision_angle: get_with_deviation(
+                    AVERAGE_VISION_ANGLE,
+                    rng,
+                )
+                .clamp(
+                    VISION_ANGLE_GENE.bounds.0,
+                    VISION_ANGLE_GENE.bounds.1,
+                ),
+                division_threshold: get_with_deviation(
+                    AVERAGE_DIVISION_THRESHOLD,
+                    rng,
+                )
+                .clamp(
+                    DIVISION_THRESHOLD_GENE.bounds.0,
+                    DIVISION_THRESHOLD_GENE.bounds.1,
+                ),
+                max_acceleration: get_with_deviation(
+                    AVERAGE_MAX_ACCELERATION,
+                    rng,
+                )
+                .clamp(
+                    MAX_ACCELERATION_GENE.bounds.0,
+                    MAX_ACCELERATION_GENE.bounds.1,
+                ),
+                max_turning_rate: get_with_deviation(
+                    AVERAGE_MAX_TURNING_RATE,
+                    rng,
+                )
+                .clamp(
+                    MAX_TURNING_RATE_GENE.bounds.0,
+                    MAX_TURNING_RATE_GENE.bounds.1,
+                ),
+                litter_size: (AVERAGE_LITTER_SIZE as f32)
+                    .clamp(
+                        LITTER_SIZE_GENE.bounds.0,
+                        LITTER_SIZE_GENE.bounds.1,
+                    )
+                    .round() as usize,
+                litter_energy_share: get_with_deviation(
+                    AVERAGE_LITTER_ENERGY_SHARE,
+                    rng,
+                )
+                .clamp(
+                    LITTER_ENERGY_SHARE_GENE.bounds.0,
+                    LITTER_ENERGY_SHARE_GENE.bounds.1,
+                ),
+                size: get_with_deviation(AVERAGE_SIZE, rng)
+                    .clamp(SIZE_GENE.bounds.0, SIZE_GENE.bounds.1),
+                eating_strategy,
+                preferences: AVERAGE_PREFERENCES.map(|preference| {
+                    get_with_deviation(preference, rng).clamp(
+                        PREFERENCES_GENE.bounds.0,
+                        PREFERENCES_GENE.bounds.1,
+                    )
+                }),
+                skills: HashSet::with_capacity(Skill::ALL.len()),
+                color,
+            }
+        }
+    }
+
+    /// Get the genome of a child.
+    pub fn mutate(&self, rng: &mut StdRng) -> Self {
+        let mut skills = self.skills.clone();
+
+        skills.retain(|skill| {
+            let inheritance_chance = skill.inheritance_chance();
+
+            inheritance_chance == 1.0
+                || rng.gen_range(0.0..1.0) <= inheritance_chance
+        });
+
+        if rng.gen_range(0.0..1.0) <= unsafe { SKILLS_CHANGE_CHANCE }
+        {
+            if random::<bool>() {
+                if let Some(random_skill) = HashSet::from(Skill::ALL)
+                    .difference(&skills)
+                    .collect::<HashSet<_>>()
+                    .iter()
+                    .choose(rng)
+                {
+                    skills.insert(**random_skill);
+                }
+            } else if let Some(random_skill) =
+                skills.clone().iter().choose(rng)
+            {
+                skills.remove(random_skill);
+            }
+        }
+
+        let eating_strategy = self.eating_strategy.get_inherited(rng);
+
+        unsafe {
+            Self {
+                speed: SPEED_GENE.mutate(self.speed, rng),
+                vision_distance: VISION_DISTANCE_GENE
+                    .mutate(self.vision_distance, rng),
+                vision_angle: VISION_ANGLE_GENE
+                    .mutate(self.vision_angle, rng),
+                division_threshold: DIVISION_THRESHOLD_GENE
+                    .mutate(self.division_threshold, rng),
+                size: SIZE_GENE.mutate(self.size, rng),
+                max_acceleration: MAX_ACCELERATION_GENE
+                    .mutate(self.max_acceleration, rng),
+                max_turning_rate: MAX_TURNING_RATE_GENE
+                    .mutate(self.max_turning_rate, rng),
+                litter_size: LITTER_SIZE_GENE
+                    .mutate(self.litter_size as f32, rng)
+                    .round() as usize,
+                litter_energy_share: LITTER_ENERGY_SHARE_GENE
+                    .mutate(self.litter_energy_share, rng),
+                eating_strategy,
+                preferences: self.preferences.map(|preference| {
+                    PREFERENCES_GENE.mutate(preference, rng)
+                }),
+                skills,
+                color: self.color,
+            }
+        }
+    }
+
+    /// Get a genome with every gene taken from either of the parents.
+    pub fn crossover(&self, other: &Self, rng: &mut StdRng) -> Self {
+        let eating_strategy = if rng.gen_bool(0.5) {
+            self.eating_strategy
+        } else {
+            other.eating_strategy
+        };
+
+        let skills = Skill::ALL
+            .into_iter()
+            .filter(|skill| {
+                if rng.gen_bool(0.5) {
+                    self.skills.contains(skill)
+                } else {
+                    other.skills.contains(skill)
+                }
+            })
+            .collect();
+
+        let litter_size = if rng.gen_bool(0.5) {
+            self.litter_size
+        } else {
+            other.litter_size
+        };
+
+        let mut pick =
+            |a: f32, b: f32| if rng.gen_bool(0.5) { a } else { b };
+
+        Self {
+            speed: pick(self.speed, other.speed),
+            vision_distance: pick(
+                self.vision_distance,
+                other.vision_distance,
+            ),
+            vision_angle: pick(self.vision_angle, other.vision_angle),
+            division_threshold: pick(
+                self.division_threshold,
+                other.division_threshold,
+            ),
+            size: pick(self.size, other.size),
+            max_acceleration: pick(
+                self.max_acceleration,
+                other.max_acceleration,
+            ),
+            max_turning_rate: pick(
+                self.max_turning_rate,
+                other.max_turning_rate,
+            ),
+            litter_size,
+            litter_energy_share: pick(
+                self.litter_energy_share,
+                other.litter_energy_share,
+            ),
+            eating_strategy,
+            preferences: Preferences {
+                body:      pick(
+                    self.preferences.body,
+                    other.preferences.body,
+                ),
+                cross:     pick(
+                    self.preferences.cross,
+                    other.preferences.cross,
+                ),
+                plant:     pick(
+                    self.preferences.plant,
+                    other.preferences.plant,
+                ),
+                energy:    pick(
+                    self.preferences.energy,
+                    other.preferences.energy,
+                ),
+                distance:  pick(
+                    self.preferences.distance,
+                    other.preferences.distance,
+                ),
+                infection: pick(
+                    self.preferences.infection,
+                    other.preferences.infection,
+                ),
+            },
+            skills,
+            color: self.color,
+        }
+    }
+
+    /// How different the genomes are, 0.0 meaning they're the same.
+    /// Every numeric gene adds its relative difference, every skill only one of the genomes has
+    /// and a different eating strategy add 1.0 each.
+    pub fn distance(&self, other: &Self) -> f32 {
+        let relative_difference = |a: f32, b: f32| {
+            let max = a.abs().max(b.abs());
+            if max == 0.0 {
+                0.0
+            } else {
+                (a - b).abs() / max
+            }
+        };
+
+        let mut distance =
+            relative_difference(self.speed, other.speed)
+                + relative_difference(
+                    self.vision_distance,
+                    other.vision_distance,
+                )
+                + relative_difference(
+                    self.vision_angle,
+                    other.vision_angle,
+                )
+                + relative_difference(
+                    self.division_threshold,
+                    other.division_threshold,
+                )
+                + relative_difference(self.size, other.size)
+                + relative_difference(
+                    self.max_acceleration,
+                    other.max_acceleration,
+                )
+                + relative_difference(
+                    self.max_turning_rate,
+                    other.max_turning_rate,
+                )
+                + relative_difference(
+                    self.litter_size as f32,
+                    other.litter_size as f32,
+                )
+                + relative_difference(
+                    self.litter_energy_share,
+                    other.litter_energy_share,
+                );
+
+        for (a, b) in self
+            .preferences
+            .as_array()
+            .into_iter()
+            .zip(other.preferences.as_array())
+        {
+            distance += relative_difference(a, b);
+        }
+
+        distance +=
+            self.skills.symmetric_difference(&other.skills).count()
+                as f32;
+
+        if self.eating_strategy != other.eating_strategy {
+            distance += 1.0;
+        }
+
+        distance
+    }
+}
+
+/// Skills are written by their names in `config.toml`, in the same order every time.
+fn serialize_skills<S: Serializer>(
+    skills: &HashSet<Skill>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut skills = skills.iter().copied().collect::<Vec<_>>();
+    skills.sort_by_key(|skill| *skill as usize);
+
+    serializer.collect_seq(skills.iter().map(|skill| skill.name()))
+}
+
+/// Colors are written as RGB.
+fn serialize_color<S: Serializer>(
+    color: &Color,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(
+        [color.r, color.g, color.b]
+            .map(|component| (component * 255.0).round() as u8),
+    )
+}
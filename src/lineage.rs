@@ -0,0 +1,281 @@
+use crate::{constants::*, user_constants::*, Body, BodyId};
+use serde_derive::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::write,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineageExportFormat {
+    /// https://en.wikipedia.org/wiki/Newick_format
+    Newick,
+    Json,
+}
+
+/// Where a body comes from.
+#[derive(Clone, Copy, PartialEq)]
+pub struct Lineage {
+    /// With sexual procreation, the parent that has found the partner.
+    pub parent_id:  Option<BodyId>,
+    pub generation: usize,
+    /// Set when the body gets into the world.
+    pub birth_tick: u64,
+}
+
+impl Lineage {
+    /// The lineage of a body of the 1st generation.
+    pub const FOUNDER: Self = Self {
+        parent_id:  None,
+        generation: 0,
+        birth_tick: 0,
+    };
+
+    #[inline(always)]
+    pub fn child_of(parent_id: &BodyId, parent: &Self) -> Self {
+        Self {
+            parent_id:  Some(*parent_id),
+            generation: parent.generation + 1,
+            birth_tick: 0,
+        }
+    }
+}
+
+struct AncestryEntry {
+    parent_id:  Option<BodyId>,
+    body_type:  u16,
+    generation: usize,
+    birth_tick: u64,
+    death_tick: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct JsonNode {
+    id:         usize,
+    parent:     Option<usize>,
+    body_type:  u16,
+    generation: usize,
+    birth_tick: u64,
+    death_tick: Option<u64>,
+}
+
+/// Every body that has lived, unless it's been pruned.
+pub struct AncestryLog {
+    entries: HashMap<BodyId, AncestryEntry>,
+}
+
+impl AncestryLog {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    #[inline(always)]
+    pub fn record_birth(
+        &mut self,
+        body_id: &BodyId,
+        body: &mut Body,
+        tick: u64,
+    ) {
+        body.lineage.birth_tick = tick;
+
+        self.entries.insert(
+            *body_id,
+            AncestryEntry {
+                parent_id:  body.lineage.parent_id,
+                body_type:  body.body_type,
+                generation: body.lineage.generation,
+                birth_tick: tick,
+                death_tick: None,
+            },
+        );
+    }
+
+    #[inline(always)]
+    pub fn record_death(&mut self, body_id: &BodyId, tick: u64) {
+        if let Some(entry) = self.entries.get_mut(body_id) {
+            entry.death_tick = Some(tick);
+        }
+    }
+
+    /// Forget the bodies that have no living descendants.
+    pub fn prune(&mut self) {
+        let mut to_keep: HashSet<BodyId> = HashSet::new();
+
+        for (body_id, entry) in &self.entries {
+            if entry.death_tick.is_some() {
+                continue;
+            }
+
+            // Walk up until the rest of the ancestors are known to be kept
+            let mut current = Some(*body_id);
+            while let Some(current_id) = current {
+                if !to_keep.insert(current_id) {
+                    break;
+                }
+
+                current = self
+                    .entries
+                    .get(&current_id)
+                    .and_then(|entry| entry.parent_id);
+            }
+        }
+
+        self.entries.retain(|body_id, _| to_keep.contains(body_id));
+    }
+
+    /// Write the log to a file in the format from `config.toml`.
+    pub fn export(&self) {
+        // Body IDs can't be written, so the bodies are numbered in the order they were born in
+        let mut body_ids =
+            self.entries.keys().copied().collect::<Vec<_>>();
+        body_ids.sort_by_key(|body_id| {
+            (self.entries[body_id].birth_tick, *body_id)
+        });
+
+        let numbers = body_ids
+            .iter()
+            .enumerate()
+            .map(|(number, body_id)| (*body_id, number))
+            .collect::<HashMap<_, _>>();
+
+        let (file_name, contents) =
+            match unsafe { LINEAGE_EXPORT_FORMAT } {
+                LineageExportFormat::Newick => (
+                    LINEAGE_NEWICK_FILE_NAME,
+                    Some(self.to_newick(&body_ids, &numbers)),
+                ),
+                LineageExportFormat::Json => (
+                    LINEAGE_JSON_FILE_NAME,
+                    self.to_json(&body_ids, &numbers),
+                ),
+            };
+
+        let Some(contents) = contents else {
+            eprintln!("The lineage couldn't be serialized.");
+            return;
+        };
+
+        if write(file_name, contents).is_err() {
+            eprintln!("The lineage file couldn't be written.");
+        }
+    }
+
+    fn to_newick(
+        &self,
+        body_ids: &[BodyId],
+        numbers: &HashMap<BodyId, usize>,
+    ) -> String {
+        let mut roots = Vec::new();
+        let mut children: HashMap<BodyId, Vec<BodyId>> =
+            HashMap::new();
+
+        // `body_ids` are sorted, so the children are too
+        for body_id in body_ids {
+            match self.entries[body_id].parent_id.filter(
+                |parent_id| self.entries.contains_key(parent_id),
+            ) {
+                Some(parent_id) => children
+                    .entry(parent_id)
+                    .or_default()
+                    .push(*body_id),
+                None => roots.push(*body_id),
+            }
+        }
+
+        // Built without recursion because the tree can be very deep
+        let mut rendered: HashMap<BodyId, String> = HashMap::new();
+        let mut stack = roots
+            .iter()
+            .map(|root| (*root, false))
+            .collect::<Vec<_>>();
+
+        while let Some((body_id, are_children_rendered)) = stack.pop()
+        {
+            if !are_children_rendered {
+                stack.push((body_id, true));
+                if let Some(body_children) = children.get(&body_id) {
+                    stack.extend(
+                        body_children
+                            .iter()
+                            .map(|child| (*child, false)),
+                    );
+                }
+
+                continue;
+            }
+
+            let entry = &self.entries[&body_id];
+            let mut node = String::new();
+
+            if let Some(body_children) = children.get(&body_id) {
+                node.push('(');
+                node.push_str(
+                    &body_children
+                        .iter()
+                        .map(|child| rendered.remove(child).unwrap())
+                        .collect::<Vec<_>>()
+                        .join(","),
+                );
+                node.push(')');
+            }
+
+            node.push_str(&format!(
+                "b{}_type{}",
+                numbers[&body_id], entry.body_type
+            ));
+
+            // The branch length is the number of ticks between the births
+            if let Some(parent) = entry
+                .parent_id
+                .and_then(|parent_id| self.entries.get(&parent_id))
+            {
+                node.push_str(&format!(
+                    ":{}",
+                    entry.birth_tick - parent.birth_tick
+                ));
+            }
+
+            rendered.insert(body_id, node);
+        }
+
+        let roots = roots
+            .iter()
+            .map(|root| rendered.remove(root).unwrap())
+            .collect::<Vec<_>>();
+
+        if roots.len() == 1 {
+            format!("{};", roots[0])
+        } else {
+            format!("({});", roots.join(","))
+        }
+    }
+
+    fn to_json(
+        &self,
+        body_ids: &[BodyId],
+        numbers: &HashMap<BodyId, usize>,
+    ) -> Option<String> {
+        let nodes = body_ids
+            .iter()
+            .map(|body_id| {
+                let entry = &self.entries[body_id];
+
+                JsonNode {
+                    id:         numbers[body_id],
+                    parent:     entry
+                        .parent_id
+                        .and_then(|parent_id| numbers.get(&parent_id))
+                        .copied(),
+                    body_type:  entry.body_type,
+                    generation: entry.generation,
+                    birth_tick: entry.birth_tick,
+                    death_tick: entry.death_tick,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_string(&nodes).ok()
+    }
+}
@@ -9,8 +9,14 @@ mod cells;
 mod condition;
 mod constants;
 mod cross;
+mod fertility;
+mod genome;
+mod lineage;
 mod plant;
+mod report;
+mod skill;
 mod smart_drawing;
+mod terrain;
 mod user_constants;
 mod utils;
 mod zoom;
@@ -20,7 +26,13 @@ use cells::*;
 use condition::*;
 use constants::*;
 use cross::*;
+use fertility::*;
+use genome::*;
+use lineage::*;
 use plant::*;
+use report::*;
+use skill::*;
+use terrain::*;
 use user_constants::*;
 use utils::*;
 use zoom::*;
@@ -42,7 +54,7 @@ use macroquad::{
     math::{Rect, Vec2},
     miniquad::{window::set_fullscreen, MouseButton},
     prelude::vec2,
-    shapes::{draw_circle_lines, draw_line},
+    shapes::draw_line,
     window::{next_frame, screen_height, screen_width, Conf},
 };
 use rand::{rngs::StdRng, seq::IteratorRandom, Rng, SeedableRng};
@@ -64,7 +76,6 @@ async fn main() {
     assert_eq!(Condition::ALL.len(), variant_count::<Condition>());
     assert_eq!(Virus::ALL.len(), variant_count::<Virus>());
     assert_eq!(Skill::ALL.len(), variant_count::<Skill>());
-    assert_eq!(PlantKind::ALL.len(), variant_count::<PlantKind>());
 
     config_setup();
 
@@ -95,6 +106,10 @@ async fn main() {
 
     let cells = generate_cells(&area_size);
 
+    unsafe {
+        TERRAIN_MAP = TerrainMap::new(&cells);
+    }
+
     // Camera
     let mut camera = Camera2D::from_display_rect(Rect::new(
         0.0,
@@ -116,8 +131,7 @@ async fn main() {
     };
 
     // Evolution stuff
-    let mut condition: Option<(Condition, (Instant, Duration))> =
-        None;
+    let mut condition: Option<ActiveCondition> = None;
 
     let mut bodies: HashMap<BodyId, Body> =
         HashMap::with_capacity(unsafe { BODIES_N });
@@ -125,11 +139,15 @@ async fn main() {
         HashMap::with_capacity(cells.rows * cells.columns);
     let mut crosses: HashMap<Cell, HashMap<CrossId, Cross>> =
         HashMap::with_capacity(cells.rows * cells.columns);
+    // Filled again every tick
+    let mut body_cells: HashMap<Cell, HashMap<BodyId, BodyPosition>> =
+        HashMap::with_capacity(cells.rows * cells.columns);
 
     for i in 0..cells.rows {
         for j in 0..cells.columns {
             plants.insert(Cell { i, j }, HashMap::new());
             crosses.insert(Cell { i, j }, HashMap::new());
+            body_cells.insert(Cell { i, j }, HashMap::new());
         }
     }
 
@@ -151,20 +169,42 @@ async fn main() {
         );
     }
 
+    // Every body type starts with one body
+    let mut founders: HashMap<u16, Genome> = bodies
+        .values()
+        .map(|body| (body.body_type, body.genome.clone()))
+        .collect();
+
+    // Needed for the speciation
+    let mut next_body_type = unsafe { BODIES_N } as u16 + 1;
+
+    // Needed for the lineage
+    let mut tick: u64 = 0;
+    let mut ancestry_log = AncestryLog::new();
+
+    for (body_id, body) in &mut bodies {
+        ancestry_log.record_birth(body_id, body, tick);
+    }
+
     // Needs to be handled manually to avoid extracting all plants out of the cells
     let mut plants_n = 0;
 
     // Spawn the plants
+    let fertility_map = FertilityMap::new(&cells);
+
     for _ in 0..unsafe { PLANTS_N } {
-        Plant::randomly_spawn_plant(
+        if Plant::randomly_spawn_plant(
             &bodies,
             &mut plants,
             &area_size,
             &cells,
+            &fertility_map,
+            true,
+            None,
             &mut rng,
-        );
-
-        plants_n += 1;
+        ) {
+            plants_n += 1;
+        }
     }
 
     // Zoom
@@ -178,7 +218,20 @@ async fn main() {
     // Needed for the FPS
     let mut last_updated = Instant::now();
 
+    // Needed for the heatwave
+    let mut last_tick = Instant::now();
+
+    let mut strategies_report = StrategiesReport::new();
+
+    let has_plant_lifetimes =
+        plant_kinds().iter().any(|kind| kind.lifetime != 0);
+
     loop {
+        tick += 1;
+
+        let tick_duration = last_tick.elapsed().as_secs_f32();
+        last_tick = Instant::now();
+
         // Handle interactions
         if unlikely(is_key_pressed(KeyCode::Escape)) {
             std::process::exit(0);
@@ -208,6 +261,15 @@ async fn main() {
             info.evolution_info.last_updated = Some(Instant::now());
         }
 
+        if unlikely(is_key_pressed(KeyCode::Key3)) {
+            export_genomes(&bodies, &founders);
+        }
+
+        if unlikely(is_key_pressed(KeyCode::Key4)) {
+            ancestry_log.prune();
+            ancestry_log.export();
+        }
+
         if zoom.zoomed {
             // There's no reason to zoom in again if the mouse position hasn't been changed
             let current_mouse_pos = Vec2::from(mouse_position());
@@ -243,20 +305,54 @@ async fn main() {
         let mut removed_crosses: HashMap<CrossId, Vec2> =
             HashMap::new();
 
-        Condition::update_condition(&mut condition, &mut rng);
+        Condition::update_condition(
+            &mut condition,
+            &area_size,
+            &mut rng,
+        );
+
+        let drought = condition.filter(|active_condition| {
+            active_condition.condition == Condition::Drought
+        });
+        let rain = condition.filter(|active_condition| {
+            active_condition.condition == Condition::Rain
+        });
 
         // Remove plants
         let n_to_remove = (plants_n as f32
             * (unsafe { PLANT_DIE_CHANCE }
-                + if condition.is_some_and(|(condition, _)| {
-                    condition == Condition::Drought
-                }) {
+                + if drought
+                    .is_some_and(|drought| drought.region.is_none())
+                {
                     (unsafe { PLANT_DIE_CHANCE })
-                        * DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER
+                        * unsafe {
+                            DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER
+                        }
                 } else {
                     0.0
                 })) as usize;
 
+        // A regional drought only makes the plants within it die more often
+        if let Some(region) =
+            drought.and_then(|drought| drought.region)
+        {
+            for cell in region.get_cells(&cells) {
+                for (plant_id, plant) in plants.get(&cell).unwrap() {
+                    if region.contains(&plant.pos)
+                        && !removed_plants.contains_key(plant_id)
+                        && rng.gen_range(0.0..1.0)
+                            < unsafe {
+                                DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER
+                            } * unsafe { PLANT_DIE_CHANCE }
+                        && !fertility_map.protects(&cell, &mut rng)
+                    {
+                        removed_plants.insert(*plant_id, plant.pos);
+                        plants_n -= 1;
+                    }
+                }
+            }
+        }
+
         for _ in 0..n_to_remove {
             loop {
                 // Pick a random cell and remove a random plant from it
@@ -269,7 +365,10 @@ async fn main() {
                     .iter()
                     .choose(&mut rng)
                 {
-                    if !removed_plants.contains_key(random_plant_id) {
+                    if !removed_plants.contains_key(random_plant_id)
+                        && !fertility_map
+                            .protects(random_cell, &mut rng)
+                    {
                         removed_plants.insert(
                             *random_plant_id,
                             random_plant.pos,
@@ -282,39 +381,102 @@ async fn main() {
             }
         }
 
+        // Remove the plants that have outlived their kind's lifetime
+        if has_plant_lifetimes {
+            for (plant_id, plant) in plants.values().flatten() {
+                if plant.is_expired()
+                    && !removed_plants.contains_key(plant_id)
+                {
+                    removed_plants.insert(*plant_id, plant.pos);
+                    plants_n -= 1;
+                }
+            }
+        }
+
         // Spawn a plant in a random place with a specific chance
         let n_to_add = unsafe { PLANTS_N_FOR_ONE_STEP }
-            + if condition.is_some_and(|(condition, _)| {
-                condition == Condition::Rain
-            }) {
+            + if rain.is_some_and(|rain| rain.region.is_none()) {
                 (unsafe { PLANTS_N_FOR_ONE_STEP } as f32
-                    * RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER)
-                    as usize
+                    * unsafe {
+                        RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER
+                    }) as usize
             } else {
                 0
             };
 
         for _ in 0..n_to_add {
-            Plant::randomly_spawn_plant(
+            if Plant::randomly_spawn_plant(
                 &bodies,
                 &mut plants,
                 &area_size,
                 &cells,
+                &fertility_map,
+                false,
+                None,
                 &mut rng,
-            );
+            ) {
+                plants_n += 1;
+            }
+        }
 
-            plants_n += 1;
+        // A regional rain spawns the extra plants within it
+        if let Some(region) = rain.and_then(|rain| rain.region) {
+            // Keep the expected number of the extra plants even if it's below one
+            let expected_n = unsafe { PLANTS_N_FOR_ONE_STEP } as f32
+                * unsafe { RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER }
+                * region.get_coverage(&area_size);
+            let n_to_add_in_region = expected_n as usize
+                + rng.gen_bool(expected_n.fract() as f64) as usize;
+
+            for _ in 0..n_to_add_in_region {
+                if Plant::randomly_spawn_plant(
+                    &bodies,
+                    &mut plants,
+                    &area_size,
+                    &cells,
+                    &fertility_map,
+                    false,
+                    Some(&region),
+                    &mut rng,
+                ) {
+                    plants_n += 1;
+                }
+            }
         }
 
         // Whether enough time has passed to draw a new frame
         let is_draw_mode = last_updated.elapsed().as_millis()
             >= Duration::from_secs(1 / FPS).as_millis();
 
+        Body::update_body_cells(&mut body_cells, &bodies, &cells);
+
         for (body_id, body) in unsafe {
             &mut (*(&mut bodies as *mut HashMap<BodyId, Body>))
         } {
+            body.condition = condition
+                .filter(|active_condition| {
+                    active_condition.covers(&body.pos)
+                })
+                .map(|active_condition| active_condition.condition);
+
+            body.handle_plague(&mut rng);
             body.handle_viruses();
-            body.handle_lifespan();
+            body.handle_lifespan(tick_duration);
+            body.handle_strike();
+
+            if body.handle_seed(
+                body_id,
+                &bodies,
+                &mut plants,
+                &cells,
+                &fertility_map,
+                &area_size,
+                &mut rng,
+            ) {
+                plants_n += 1;
+            }
+
+            body.handle_tick_skills(body_id, &cells, &body_cells);
 
             // Handle if dead to become a cross
             if body.energy < unsafe { MIN_ENERGY }
@@ -334,17 +496,7 @@ async fn main() {
             let mut chasers = body.followed_by.clone();
 
             if !chasers.is_empty() {
-                if body
-                    .skills
-                    .contains(&Skill::PrioritizeFasterChasers)
-                    && chasers.iter().any(|(_, other_body)| {
-                        other_body.speed > body.speed
-                    })
-                {
-                    chasers.retain(|_, other_body| {
-                        other_body.speed > body.speed
-                    })
-                }
+                body.handle_chase_reaction_skills(&mut chasers);
 
                 if let Some((
                     closest_chasing_body_id,
@@ -372,17 +524,27 @@ async fn main() {
                         &mut plants,
                     );
 
-                    let distance_to_closest_chasing_body =
-                        body.pos.distance(closest_chasing_body.pos);
+                    match body.handle_escape_skills(
+                        &chasers, &cells, &plants,
+                    ) {
+                        Some(escape_direction) => {
+                            body.move_with(
+                                escape_direction * body.get_speed(),
+                            );
+                        }
+                        None => {
+                            let distance_to_closest_chasing_body =
+                                body.pos.distance(
+                                    closest_chasing_body.pos,
+                                );
 
-                    body.pos.x -= (closest_chasing_body.pos.x
-                        - body.pos.x)
-                        * (body.speed
-                            / distance_to_closest_chasing_body);
-                    body.pos.y -= (closest_chasing_body.pos.y
-                        - body.pos.y)
-                        * (body.speed
-                            / distance_to_closest_chasing_body);
+                            body.move_with(
+                                (body.pos - closest_chasing_body.pos)
+                                    * (body.get_speed()
+                                        / distance_to_closest_chasing_body),
+                            );
+                        }
+                    }
 
                     body.wrap(&area_size);
 
@@ -391,9 +553,6 @@ async fn main() {
             }
 
             // Eating
-            let mut food: Option<FoodInfo> = None;
-
-            // Find the closest plant
             let mut visible_crosses: HashMap<&CrossId, &Cross> =
                 HashMap::new();
 
@@ -410,140 +569,126 @@ async fn main() {
                 visible_crosses
             );
 
-            // Find the closest cross
-            match visible_crosses
+            let mut visible_plants: HashMap<&PlantId, &Plant> =
+                HashMap::new();
+
+            get_visible!(body, cells, plants, visible_plants);
+
+            visible_plants.retain(|plant_id, _| {
+                !removed_plants.contains_key(plant_id)
+            });
+
+            body.handle_sight_skills(
+                visible_crosses
+                    .values()
+                    .map(|cross| (cross.pos, cross.energy))
+                    .chain(visible_plants.values().map(|plant| {
+                        (plant.pos, plant.get_contained_energy())
+                    })),
+            );
+
+            let mut visible_bodies: HashMap<&BodyId, &BodyPosition> =
+                HashMap::new();
+
+            get_visible!(body, cells, body_cells, visible_bodies);
+
+            // Find the food the body prefers the most
+            let food = visible_crosses
                 .iter()
                 .filter(|(_, cross)| {
-                    body.handle_eat_crosses_of_my_type(cross)
-                        && body.handle_alive_when_arrived_cross(cross)
-                        && body.handle_profitable_when_arrived_cross(
-                            cross,
-                        )
-                        && body.handle_avoid_new_viruses_cross(cross)
-                        && body.handle_will_arrive_first_cross(
-                            body_id, cross,
-                        )
-                        && body.handle_do_not_compete_with_relatives(
-                            &body_id,
-                            &cross.followed_by,
-                        )
+                    body.handle_food_filter_skills(
+                        body_id,
+                        Food::Cross(cross),
+                    )
                 })
-                .min_by(|(_, a), (_, b)| {
-                    body.pos
-                        .distance(a.pos)
-                        .partial_cmp(&body.pos.distance(b.pos))
-                        .unwrap()
-                }) {
-                Some((closest_cross_id, closest_cross)) => {
-                    food = Some(FoodInfo {
-                        id:        **closest_cross_id,
-                        food_type: ObjectType::Cross,
-                        pos:       closest_cross.pos,
-                        energy:    closest_cross.energy,
-                        viruses:   Some(&closest_cross.viruses),
-                    });
-                }
-                None => {
-                    let mut visible_plants: HashMap<
-                        &PlantId,
-                        &Plant,
-                    > = HashMap::new();
-
-                    get_visible!(body, cells, plants, visible_plants);
-
-                    let filtered_visible_plants = visible_plants
+                .map(|(cross_id, cross)| FoodInfo {
+                    id:        **cross_id,
+                    food_type: ObjectType::Cross,
+                    pos:       cross.pos,
+                    energy:    cross.energy,
+                    viruses:   Some(&cross.viruses),
+                })
+                .chain(
+                    visible_plants
                         .iter()
-                        .filter(|(plant_id, plant)| {
-                            !removed_plants.contains_key(plant_id)
-                            && body.handle_alive_when_arrived_plant(plant)
-                            && body.handle_profitable_when_arrived_plant(plant)
-                            && body.handle_do_not_compete_with_relatives(
-                                &body_id,
-                                &plant.followed_by
-                            )
-                            && body.handle_will_arrive_first_plant(
+                        .filter(|(_, plant)| {
+                            body.handle_food_filter_skills(
                                 body_id,
-                                plant,
+                                Food::Plant(plant),
                             )
-                        }).collect::<Vec<_>>();
-
-                    let mut closest_plant = body.find_closest_plant(
-                        &filtered_visible_plants,
-                        PlantKind::Banana,
-                    );
-
-                    if closest_plant.is_none() {
-                        closest_plant = body.find_closest_plant(
-                            &filtered_visible_plants,
-                            PlantKind::Grass,
-                        );
-                    }
-
-                    match closest_plant {
-                        Some((closest_plant_id, closest_plant)) => {
-                            food = Some(FoodInfo {
-                                id:        ***closest_plant_id,
-                                food_type: ObjectType::Plant,
-                                pos:       closest_plant.pos,
-                                energy:    closest_plant
-                                    .get_contained_energy(),
-                                viruses:   None,
-                            })
-                        }
-                        None => {
-                            // Find the closest body
-                            if let Some((closest_body_id, closest_body)) =  unsafe {
-                                &(*(&bodies as *const HashMap<BodyId, Body>))
+                        })
+                        .map(|(plant_id, plant)| FoodInfo {
+                            id:        **plant_id,
+                            food_type: ObjectType::Plant,
+                            pos:       plant.pos,
+                            energy:    plant.get_contained_energy(),
+                            viruses:   None,
+                        }),
+                )
+                .chain(
+                    visible_bodies
+                        .keys()
+                        .filter_map(|other_body_id| {
+                            unsafe {
+                                &(*(&bodies
+                                    as *const HashMap<BodyId, Body>))
                             }
-                                .iter()
-                                .filter(|(other_body_id, other_body)| {
-                                    body.body_type != other_body.body_type &&
-                                    &body_id != other_body_id
-                                    && body.energy > other_body.energy
-                                    && body.pos.distance(other_body.pos)
-                                    <= body.vision_distance
-                                    && !removed_bodies.contains(other_body_id)
-                                    && body.handle_alive_when_arrived_body(
-                                        other_body,
-                                    )
-                                    && body.handle_profitable_when_arrived_body(
-                                        other_body,
-                                    )
-                                    && body.handle_avoid_new_viruses_body(other_body)
-                                    && body.handle_will_arrive_first_body(
-                                        body_id,
-                                        other_body,
-                                    )
-                                    && body.handle_do_not_compete_with_relatives(
-                                        &body_id,
-                                        &other_body.followed_by
-                                    )
-                                })
-                                .min_by(|(_, a), (_, b)| {
-                                    body.pos
-                                        .distance(a.pos)
-                                        .partial_cmp(&body.pos.distance(b.pos))
-                                        .unwrap()
-                                })
-                            {
-                                food = Some(FoodInfo {
-                                    id:        *closest_body_id,
-                                    food_type: ObjectType::Body,
-                                    pos:       closest_body.pos,
-                                    energy:    closest_body.energy,
-                                    viruses: Some(&closest_body.viruses)
-                                })
+                            .get_key_value(*other_body_id)
+                        })
+                        .filter(|(other_body_id, other_body)| {
+                            body.body_type != other_body.body_type
+                                && &body_id != other_body_id
+                                && body.can_swallow(other_body)
+                                && body.get_attack_energy(
+                                    body_id, other_body,
+                                ) > other_body.energy
+                                && body.pos.distance(other_body.pos)
+                                    <= body.get_vision_distance()
+                                        * other_body.get_visibility()
+                                && body.can_see(other_body.pos)
+                                && !removed_bodies
+                                    .contains(other_body_id)
+                                && body.handle_food_filter_skills(
+                                    body_id,
+                                    Food::Body(other_body),
+                                )
+                        })
+                        .map(|(other_body_id, other_body)| {
+                            FoodInfo {
+                                id:        *other_body_id,
+                                food_type: ObjectType::Body,
+                                pos:       other_body.pos,
+                                energy:    other_body.energy,
+                                viruses:   Some(&other_body.viruses),
                             }
-                        }
-                    }
-                }
-            }
+                        }),
+                )
+                .max_by(|a, b| {
+                    body.get_food_score(a)
+                        .total_cmp(&body.get_food_score(b))
+                });
 
             if let Some(food) = food {
                 let distance_to_food = body.pos.distance(food.pos);
-                if distance_to_food <= body.speed {
-                    body.energy += food.energy;
+                if distance_to_food <= body.get_eating_distance() {
+                    body.energy += match food.food_type {
+                        ObjectType::Body => body
+                            .get_caught_body_energy(
+                                body_id,
+                                &food.id,
+                                &mut bodies,
+                                &removed_bodies,
+                            ),
+                        ObjectType::Cross | ObjectType::Plant => {
+                            food.energy
+                        }
+                    };
+                    // The rest of the energy is lost
+                    body.energy =
+                        body.energy.min(body.get_max_energy());
                     body.pos = food.pos;
+                    // The body stops to eat
+                    body.velocity = Vec2::ZERO;
 
                     match food.food_type {
                         ObjectType::Body => {
@@ -555,11 +700,32 @@ async fn main() {
                             removed_crosses.insert(food.id, food.pos);
                         }
                         ObjectType::Plant => {
+                            let plant = &plants
+                                [&cells.get_cell_by_pos(&food.pos)]
+                                [&food.id];
+
+                            body.get_poisoned(plant);
+
+                            if unsafe { SEED_CARRY_CHANCE } != 0.0
+                                && rng.gen_range(0.0..1.0)
+                                    < unsafe { SEED_CARRY_CHANCE }
+                            {
+                                body.seed = Some((
+                                    plant.kind,
+                                    Instant::now(),
+                                ));
+                            }
+
                             removed_plants.insert(food.id, food.pos);
                             plants_n -= 1;
                         }
                     }
                 } else {
+                    if body.handle_wait_for_prey_skills(&food) {
+                        body.move_with(Vec2::ZERO);
+                        continue;
+                    }
+
                     Body::followed_by_cleanup(
                         &body_id,
                         &cells,
@@ -616,10 +782,31 @@ async fn main() {
                         food.food_type,
                     );
 
-                    body.pos.x += (food.pos.x - body.pos.x)
-                        * (body.speed / distance_to_food);
-                    body.pos.y += (food.pos.y - body.pos.y)
-                        * (body.speed / distance_to_food);
+                    // Where the body moves to get to the food
+                    let aim_pos = match food.food_type {
+                        ObjectType::Body => body
+                            .handle_pursuit_skills(
+                                unsafe {
+                                    &(*(&bodies
+                                        as *const HashMap<
+                                            BodyId,
+                                            Body,
+                                        >))
+                                }
+                                .get(&food.id)
+                                .unwrap(),
+                            )
+                            .clamp(Vec2::ZERO, area_size),
+                        ObjectType::Cross | ObjectType::Plant => {
+                            food.pos
+                        }
+                    };
+
+                    // The aim can be right where the body is
+                    body.move_with(
+                        (aim_pos - body.pos).normalize_or_zero()
+                            * body.get_speed(),
+                    );
 
                     continue;
                 }
@@ -628,8 +815,14 @@ async fn main() {
             // Procreate
             if body.handle_procreation(
                 body_id,
+                &cells,
+                &mut bodies,
+                &body_cells,
+                &mut crosses,
+                &mut plants,
                 &mut new_bodies,
                 &mut removed_bodies,
+                &area_size,
                 &mut rng,
             ) {
                 continue;
@@ -639,6 +832,7 @@ async fn main() {
                 &body_id,
                 &cells,
                 &mut bodies,
+                &body_cells,
                 &mut crosses,
                 &mut plants,
                 &area_size,
@@ -680,12 +874,28 @@ async fn main() {
             }
 
             bodies.remove(body_id);
+            ancestry_log.record_death(body_id, tick);
         }
 
-        for (new_body_id, new_body) in new_bodies {
+        for (new_body_id, mut new_body) in new_bodies {
+            new_body.handle_speciation(
+                &mut founders,
+                &mut next_body_type,
+            );
+            ancestry_log.record_birth(
+                &new_body_id,
+                &mut new_body,
+                tick,
+            );
             bodies.insert(new_body_id, new_body);
         }
 
+        if strategies_report.as_mut().is_some_and(
+            |strategies_report| !strategies_report.update(&bodies),
+        ) {
+            strategies_report = None;
+        }
+
         for (plant_id, plant_pos) in &removed_plants {
             plants
                 .get_mut(&cells.get_cell_by_pos(plant_pos))
@@ -695,6 +905,8 @@ async fn main() {
 
         if is_draw_mode {
             if !is_key_down(KeyCode::Space) {
+                terrain_map().draw();
+
                 if zoom.zoomed {
                     for plant in Plant::get_plants_to_draw(
                         &cells,
@@ -712,13 +924,7 @@ async fn main() {
 
                         if info.body_info {
                             if drawing_strategy.vision_distance {
-                                draw_circle_lines(
-                                    body.pos.x,
-                                    body.pos.y,
-                                    body.vision_distance,
-                                    2.0,
-                                    body.color,
-                                );
+                                body.draw_vision();
                             }
 
                             if drawing_strategy.target_line {
@@ -768,6 +974,10 @@ async fn main() {
                     }
                 }
 
+                if let Some(active_condition) = condition {
+                    active_condition.draw();
+                }
+
                 last_updated = Instant::now();
             }
 
@@ -778,6 +988,13 @@ async fn main() {
                     &mut info,
                     plants_n,
                     bodies.len(),
+                    bodies
+                        .values()
+                        .filter(|body| {
+                            body.genome.eating_strategy
+                                == EatingStrategy::Passive
+                        })
+                        .count(),
                     &condition,
                 );
             }
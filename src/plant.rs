@@ -1,81 +1,201 @@
-use crate::{constants::*, Body, BodyId, Cell, Cells, Zoom};
+use crate::{
+    constants::*, terrain_map, user_constants::*, Body, BodyId, Cell,
+    Cells, FertilityMap, Region, Zoom,
+};
 use macroquad::{
-    color::{GREEN, YELLOW},
+    color::Color,
     math::Vec2,
     prelude::vec2,
-    shapes::{draw_triangle, draw_triangle_lines},
+    shapes::{
+        draw_circle, draw_circle_lines, draw_triangle,
+        draw_triangle_lines,
+    },
 };
-use rand::{prelude::IteratorRandom, rngs::StdRng, Rng};
+use rand::{rngs::StdRng, seq::IteratorRandom, Rng};
+use serde_derive::Deserialize;
 use std::{
     collections::HashMap,
+    f32::consts::PI,
+    ptr::addr_of,
     time::{Duration, Instant},
 };
 
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlantShape {
+    Triangle,
+    TriangleLines,
+    Circle,
+    CircleLines,
+}
+
 #[derive(Clone, Copy, PartialEq)]
-pub enum PlantKind {
-    Grass,
-    Banana,
+pub struct PlantKind {
+    pub energy:               f32,
+    /// How likely the kind is to be picked when a plant is spawned compared to the rest.
+    pub spawn_weight:         f32,
+    /// How long a plant of the kind lives in seconds; 0 means forever.
+    pub lifetime:             u64,
+    /// How long a plant of the kind grows to its full energy in seconds.
+    pub growth_time:          f32,
+    /// The fertility of the cells the kind spawns in.
+    pub fertility_range:      (f32, f32),
+    /// The energy a body loses after eating a plant of the kind.
+    pub poison_energy_loss:   f32,
+    /// The lifespan a body loses after eating a plant of the kind.
+    pub poison_lifespan_loss: f32,
+    pub color:                Color,
+    pub shape:                PlantShape,
+}
+
+/// Filled from `config.toml`, indexed by `Plant::kind`.
+pub static mut PLANT_KINDS: Vec<PlantKind> = Vec::new();
+
+#[inline(always)]
+pub fn plant_kinds() -> &'static [PlantKind] {
+    unsafe { &*addr_of!(PLANT_KINDS) }
 }
 
 impl PlantKind {
-    pub const ALL: [Self; 2] = [Self::Grass, Self::Banana];
+    #[inline(always)]
+    /// Pick a random kind that can spawn with the fertility taking the spawn weights into
+    /// account. Returns `None` if there's no such kind.
+    pub fn choose(fertility: f32, rng: &mut StdRng) -> Option<usize> {
+        let kinds = plant_kinds()
+            .iter()
+            .enumerate()
+            .filter(|(_, kind)| kind.can_grow_with(fertility))
+            .collect::<Vec<_>>();
+        let total_weight = kinds
+            .iter()
+            .map(|(_, kind)| kind.spawn_weight)
+            .sum::<f32>();
+
+        if total_weight == 0.0 {
+            return None;
+        }
+
+        let mut weight = rng.gen_range(0.0..total_weight);
+        for (kind_id, kind) in &kinds {
+            if weight < kind.spawn_weight {
+                return Some(*kind_id);
+            }
+
+            weight -= kind.spawn_weight;
+        }
+
+        // Can only be reached because of the float precision
+        kinds.last().map(|(kind_id, _)| *kind_id)
+    }
+
+    #[inline(always)]
+    pub fn is_poisonous(&self) -> bool {
+        self.poison_energy_loss > 0.0
+            || self.poison_lifespan_loss > 0.0
+    }
+
+    #[inline(always)]
+    pub fn can_grow_with(&self, fertility: f32) -> bool {
+        (self.fertility_range.0..=self.fertility_range.1)
+            .contains(&fertility)
+    }
 }
 
 #[derive(PartialEq)]
 pub struct Plant {
-    pub pos:         Vec2,
-    pub kind:        PlantKind,
-    pub followed_by: HashMap<BodyId, Body>,
+    pub pos:           Vec2,
+    /// The index of the kind in `PLANT_KINDS`.
+    pub kind:          usize,
+    /// When the plant was spawned.
+    pub timestamp:     Instant,
+    /// How long the plant had been growing before it was spawned.
+    pub growth_offset: Duration,
+    pub followed_by:   HashMap<BodyId, Body>,
 }
 
 pub type PlantId = Instant;
 
 impl Plant {
+    #[inline(always)]
+    pub fn get_kind(&self) -> &'static PlantKind {
+        &plant_kinds()[self.kind]
+    }
+
+    #[inline(always)]
+    /// How grown the plant is, from 0.0 for a seedling to 1.0 for a fully grown plant.
+    pub fn get_growth(&self) -> f32 {
+        let growth_time = self.get_kind().growth_time;
+        if growth_time == 0.0 {
+            1.0
+        } else {
+            ((self.timestamp.elapsed() + self.growth_offset)
+                .as_secs_f32()
+                / growth_time)
+                .min(1.0)
+        }
+    }
+
     #[inline(always)]
     pub fn draw(&self) {
-        match self.kind {
-            PlantKind::Grass => {
-                draw_triangle_lines(
-                    vec2(self.pos.x, self.pos.y - OBJECT_RADIUS),
-                    vec2(
-                        self.pos.x
-                            + OBJECT_RADIUS * COSINE_OF_30_DEGREES,
-                        self.pos.y + OBJECT_RADIUS / 2.0,
-                    ),
-                    vec2(
-                        self.pos.x
-                            - OBJECT_RADIUS * COSINE_OF_30_DEGREES,
-                        self.pos.y + OBJECT_RADIUS / 2.0,
-                    ),
-                    2.0,
-                    GREEN,
+        let kind = self.get_kind();
+        let radius = OBJECT_RADIUS
+            * (SEEDLING_SIZE
+                + (1.0 - SEEDLING_SIZE) * self.get_growth());
+
+        let (v1, v2, v3) = (
+            vec2(self.pos.x, self.pos.y - radius),
+            vec2(
+                self.pos.x + radius * COSINE_OF_30_DEGREES,
+                self.pos.y + radius / 2.0,
+            ),
+            vec2(
+                self.pos.x - radius * COSINE_OF_30_DEGREES,
+                self.pos.y + radius / 2.0,
+            ),
+        );
+
+        match kind.shape {
+            PlantShape::Triangle => {
+                draw_triangle(v1, v2, v3, kind.color);
+            }
+            PlantShape::TriangleLines => {
+                draw_triangle_lines(v1, v2, v3, 2.0, kind.color);
+            }
+            PlantShape::Circle => {
+                draw_circle(
+                    self.pos.x,
+                    self.pos.y,
+                    radius / 2.0,
+                    kind.color,
                 );
             }
-            PlantKind::Banana => {
-                draw_triangle(
-                    vec2(self.pos.x, self.pos.y - OBJECT_RADIUS),
-                    vec2(
-                        self.pos.x
-                            + OBJECT_RADIUS * COSINE_OF_30_DEGREES,
-                        self.pos.y + OBJECT_RADIUS / 2.0,
-                    ),
-                    vec2(
-                        self.pos.x
-                            - OBJECT_RADIUS * COSINE_OF_30_DEGREES,
-                        self.pos.y + OBJECT_RADIUS / 2.0,
-                    ),
-                    YELLOW,
+            PlantShape::CircleLines => {
+                draw_circle_lines(
+                    self.pos.x,
+                    self.pos.y,
+                    radius / 2.0,
+                    2.0,
+                    kind.color,
                 );
             }
         }
     }
 
     #[inline(always)]
+    /// Seedlings start with a part of the energy and gain the rest while growing.
     pub fn get_contained_energy(&self) -> f32 {
-        match self.kind {
-            PlantKind::Grass => GRASS_ENERGY,
-            PlantKind::Banana => BANANA_ENERGY,
-        }
+        let seedling_energy_share = unsafe { SEEDLING_ENERGY_SHARE };
+        self.get_kind().energy
+            * (seedling_energy_share
+                + (1.0 - seedling_energy_share) * self.get_growth())
+    }
+
+    #[inline(always)]
+    /// Whether the plant has lived longer than its kind allows.
+    pub fn is_expired(&self) -> bool {
+        let lifetime = self.get_kind().lifetime;
+        lifetime != 0
+            && self.timestamp.elapsed().as_secs() >= lifetime
     }
 
     #[inline(always)]
@@ -150,15 +270,33 @@ impl Plant {
     }
 
     #[inline(always)]
-    /// Spawn a plant to a random position on the field.
+    /// Spawn a plant to a random position on the field. The plant is a seedling unless
+    /// it's `grown`. The plant spawns within the `region` if there is one. Returns if the
+    /// plant has been spawned.
+    #[allow(clippy::too_many_arguments)]
     pub fn randomly_spawn_plant(
         bodies: &HashMap<BodyId, Body>,
         plants: &mut HashMap<Cell, HashMap<PlantId, Self>>,
         area_size: &Vec2,
         cells: &Cells,
+        fertility_map: &FertilityMap,
+        grown: bool,
+        region: Option<&Region>,
         rng: &mut StdRng,
-    ) {
+    ) -> bool {
+        // Maybe grow from a seed of an existing plant
+        let parent = if region.is_none()
+            && unsafe { SEED_DISPERSAL_CHANCE } != 0.0
+            && rng.gen_range(0.0..1.0)
+                < unsafe { SEED_DISPERSAL_CHANCE }
+        {
+            Plant::get_random_plant(plants, cells, rng)
+        } else {
+            None
+        };
+
         let mut pos = Vec2::default();
+        let mut kind = None;
 
         let starting_point = Instant::now();
 
@@ -169,10 +307,25 @@ impl Plant {
                 >= Duration::from_millis(PLANT_SPAWN_TIME_LIMIT)
                     .as_nanos()
             {
-                return;
+                return false;
+            }
+            match parent {
+                Some((parent_pos, _)) => {
+                    pos = parent_pos
+                        + Vec2::from_angle(
+                            rng.gen_range(0.0..2.0 * PI),
+                        ) * rng.gen_range(
+                            0.0..=unsafe { SEED_DISPERSAL_RADIUS },
+                        );
+                }
+                None => match region {
+                    Some(region) => pos = region.get_random_pos(rng),
+                    None => {
+                        pos.x = rng.gen_range(0.0..area_size.x);
+                        pos.y = rng.gen_range(0.0..area_size.y);
+                    }
+                },
             }
-            pos.x = rng.gen_range(0.0..area_size.x);
-            pos.y = rng.gen_range(0.0..area_size.y);
             (pos.x <= OBJECT_RADIUS + MIN_GAP
                 || pos.x >= area_size.x - OBJECT_RADIUS - MIN_GAP)
                 || (pos.y <= OBJECT_RADIUS + MIN_GAP
@@ -181,18 +334,97 @@ impl Plant {
                     body.pos.distance(pos)
                         <= OBJECT_RADIUS * 2.0 + MIN_GAP
                 })
+                || terrain_map().is_blocked(&pos)
+                || (parent.is_some()
+                    && Plant::is_cell_full(plants, cells, &pos))
+                || !fertility_map
+                    .allows_spawn(&cells.get_cell_by_pos(&pos), rng)
+                || {
+                    // The kind has to be able to grow in the cell
+                    let fertility = fertility_map
+                        .get(&cells.get_cell_by_pos(&pos));
+                    kind = match parent {
+                        Some((_, parent_kind)) => Some(parent_kind)
+                            .filter(|parent_kind| {
+                                plant_kinds()[*parent_kind]
+                                    .can_grow_with(fertility)
+                            }),
+                        None => PlantKind::choose(fertility, rng),
+                    };
+                    kind.is_none()
+                }
         } {}
 
+        Plant::spawn_plant(plants, cells, pos, kind.unwrap(), grown);
+
+        true
+    }
+
+    #[inline(always)]
+    /// Put a plant of the kind to the position.
+    pub fn spawn_plant(
+        plants: &mut HashMap<Cell, HashMap<PlantId, Self>>,
+        cells: &Cells,
+        pos: Vec2,
+        kind: usize,
+        grown: bool,
+    ) {
+        let now = Instant::now();
+
         plants
             .get_mut(&cells.get_cell_by_pos(&pos))
             .unwrap()
             .insert(
-                Instant::now(),
+                now,
                 Self {
                     pos,
-                    kind: *PlantKind::ALL.iter().choose(rng).unwrap(),
+                    kind,
+                    timestamp: now,
+                    // A grown plant is treated as if it had already been growing long enough
+                    growth_offset: if grown {
+                        Duration::from_secs_f32(
+                            plant_kinds()[kind].growth_time,
+                        )
+                    } else {
+                        Duration::ZERO
+                    },
                     followed_by: HashMap::new(),
                 },
             );
     }
+
+    #[inline(always)]
+    /// Whether no more plants can grow from seeds in the cell of the position.
+    pub fn is_cell_full(
+        plants: &HashMap<Cell, HashMap<PlantId, Self>>,
+        cells: &Cells,
+        pos: &Vec2,
+    ) -> bool {
+        plants.get(&cells.get_cell_by_pos(pos)).unwrap().len()
+            >= unsafe { MAX_PLANTS_PER_CELL }
+    }
+
+    #[inline(always)]
+    /// Get the position and the kind of a random plant. Only a few cells are tried,
+    /// so `None` may be returned even if there are plants.
+    fn get_random_plant(
+        plants: &HashMap<Cell, HashMap<PlantId, Self>>,
+        cells: &Cells,
+        rng: &mut StdRng,
+    ) -> Option<(Vec2, usize)> {
+        for _ in 0..SEED_PARENT_ATTEMPTS_N {
+            let random_cell = Cell {
+                i: rng.gen_range(0..cells.rows),
+                j: rng.gen_range(0..cells.columns),
+            };
+
+            if let Some(plant) =
+                plants.get(&random_cell).unwrap().values().choose(rng)
+            {
+                return Some((plant.pos, plant.kind));
+            }
+        }
+
+        None
+    }
 }
@@ -0,0 +1,138 @@
+use crate::{
+    constants::*, user_constants::*, Body, BodyId, EatingStrategy,
+    Genome,
+};
+use serde_derive::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{write, File},
+    io::Write,
+    time::Instant,
+};
+
+/// Writes how many passive and active bodies every body type has over time.
+pub struct StrategiesReport {
+    file:         File,
+    start:        Instant,
+    last_written: Instant,
+}
+
+impl StrategiesReport {
+    /// Create the report file. Returns `None` if the report is turned off or the file
+    /// couldn't be created, in which case the evolution goes on without the report.
+    pub fn new() -> Option<Self> {
+        if unsafe { STRATEGIES_REPORT_INTERVAL } == 0 {
+            return None;
+        }
+
+        let Ok(mut file) = File::create(STRATEGIES_REPORT_FILE_NAME)
+        else {
+            eprintln!(
+                "The strategies report file couldn't be created, so the report is turned off."
+            );
+            return None;
+        };
+
+        if writeln!(file, "time,body_type,passive,active").is_err() {
+            eprintln!(
+                "The strategies report couldn't be written, so it's turned off."
+            );
+            return None;
+        }
+
+        Some(Self {
+            file,
+            start: Instant::now(),
+            last_written: Instant::now(),
+        })
+    }
+
+    #[inline(always)]
+    /// Write the current numbers if enough time has passed. Returns whether the report can
+    /// go on.
+    pub fn update(&mut self, bodies: &HashMap<BodyId, Body>) -> bool {
+        if self.last_written.elapsed().as_secs()
+            < unsafe { STRATEGIES_REPORT_INTERVAL }
+        {
+            return true;
+        }
+
+        // Sorted by the body type to make the report easier to read
+        let mut strategies: BTreeMap<u16, (usize, usize)> =
+            BTreeMap::new();
+
+        for body in bodies.values() {
+            let (passive_n, active_n) =
+                strategies.entry(body.body_type).or_default();
+
+            match body.genome.eating_strategy {
+                EatingStrategy::Passive => *passive_n += 1,
+                EatingStrategy::Active => *active_n += 1,
+            }
+        }
+
+        let time = self.start.elapsed().as_secs();
+
+        for (body_type, (passive_n, active_n)) in strategies {
+            if writeln!(
+                self.file,
+                "{},{},{},{}",
+                time, body_type, passive_n, active_n
+            )
+            .is_err()
+            {
+                eprintln!(
+                    "The strategies report couldn't be written, so it's turned off."
+                );
+                return false;
+            }
+        }
+
+        self.last_written = Instant::now();
+        true
+    }
+}
+
+#[derive(Serialize)]
+struct GenomeRecord<'a> {
+    body_type:           u16,
+    /// How far the genome has drifted from the genome the body type started with.
+    distance_to_founder: f32,
+    genome:              &'a Genome,
+}
+
+#[derive(Serialize)]
+struct GenomesExport<'a> {
+    genomes: Vec<GenomeRecord<'a>>,
+}
+
+/// Write the genomes of all the bodies to a file.
+pub fn export_genomes(
+    bodies: &HashMap<BodyId, Body>,
+    founders: &HashMap<u16, Genome>,
+) {
+    let mut genomes = bodies
+        .values()
+        .map(|body| GenomeRecord {
+            body_type:           body.body_type,
+            distance_to_founder: body
+                .genome
+                .distance(&founders[&body.body_type]),
+            genome:              &body.genome,
+        })
+        .collect::<Vec<_>>();
+
+    genomes.sort_by_key(|record| record.body_type);
+
+    let contents = match toml::to_string(&GenomesExport { genomes }) {
+        Ok(contents) => contents,
+        Err(_) => {
+            eprintln!("The genomes couldn't be serialized.");
+            return;
+        }
+    };
+
+    if write(GENOMES_FILE_NAME, contents).is_err() {
+        eprintln!("The genomes file couldn't be written.");
+    }
+}
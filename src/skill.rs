@@ -0,0 +1,443 @@
+use crate::{
+    constants::*, get_visible, user_constants::*, Body, BodyId,
+    BodyPosition, Cell, Cells, Cross, CrossId, FoodInfo, Plant,
+    PlantId,
+};
+use macroquad::math::Vec2;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Instant,
+};
+
+#[derive(Eq, Hash, PartialEq, Copy, Clone)]
+/// https://github.com/kul-sudo/eportal/blob/main/README.md#skills
+pub enum Skill {
+    DoNotCompeteWithRelatives,
+    AliveWhenArrived,
+    ProfitableWhenArrived,
+    PrioritizeFasterChasers,
+    AvoidNewViruses,
+    WillArriveFirst,
+    EatCrossesOfMyType,
+    AvoidInfectedCrosses,
+    Intercept,
+    Evade,
+    Memory,
+    Herd,
+    PackHunter,
+    Ambush,
+    DetectPoison,
+}
+
+/// The part of the body's behaviour a skill hooks into.
+#[derive(PartialEq, Eq)]
+pub enum SkillHook {
+    /// Decides whether a visible object is worth following.
+    FoodFilter,
+    /// Decides how the body reacts to being chased.
+    ChaseReaction,
+    /// Decides where the body aims when chasing a living body.
+    Pursuit,
+    /// Decides what the body does when it sees no food.
+    Idle,
+    /// Takes in the food the body sees.
+    Sight,
+    /// Updates what the body knows about its surroundings every tick.
+    Tick,
+    /// Decides when the body goes for a living body, how strongly it attacks it and how much
+    /// energy it gets from catching it.
+    Catch,
+}
+
+#[derive(Clone, Copy)]
+pub struct SkillProperties {
+    /// Energy constantly spent on having the skill.
+    pub energy_cost:        f32,
+    /// The probability a body's child inherits the skill.
+    pub inheritance_chance: f32,
+}
+
+/// Filled from `config.toml`, indexed by `Skill as usize`.
+pub static mut SKILL_PROPERTIES: [SkillProperties; Skill::ALL.len()] =
+    [SkillProperties {
+        energy_cost:        0.0,
+        inheritance_chance: 0.0,
+    }; Skill::ALL.len()];
+
+/// A place where a body has seen food.
+#[derive(Clone, PartialEq)]
+pub struct RememberedFood {
+    pub pos:       Vec2,
+    pub energy:    f32,
+    pub timestamp: Instant,
+}
+
+/// An object a body can eat.
+#[derive(Clone, Copy)]
+pub enum Food<'a> {
+    Body(&'a Body),
+    Cross(&'a Cross),
+    Plant(&'a Plant),
+}
+
+impl Food<'_> {
+    #[inline(always)]
+    pub fn followed_by(&self) -> &HashMap<BodyId, Body> {
+        match self {
+            Food::Body(body) => &body.followed_by,
+            Food::Cross(cross) => &cross.followed_by,
+            Food::Plant(plant) => &plant.followed_by,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+impl Skill {
+    pub const ALL: [Self; 15] = [
+        Self::DoNotCompeteWithRelatives,
+        Self::AliveWhenArrived,
+        Self::ProfitableWhenArrived,
+        Self::PrioritizeFasterChasers,
+        Self::AvoidNewViruses,
+        Self::WillArriveFirst,
+        Self::EatCrossesOfMyType,
+        Self::AvoidInfectedCrosses,
+        Self::Intercept,
+        Self::Evade,
+        Self::Memory,
+        Self::Herd,
+        Self::PackHunter,
+        Self::Ambush,
+        Self::DetectPoison,
+    ];
+
+    /// The name of the skill in `config.toml`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::DoNotCompeteWithRelatives => {
+                "do_not_compete_with_relatives"
+            }
+            Self::AliveWhenArrived => "alive_when_arrived",
+            Self::ProfitableWhenArrived => "profitable_when_arrived",
+            Self::PrioritizeFasterChasers => {
+                "prioritize_faster_chasers"
+            }
+            Self::AvoidNewViruses => "avoid_new_viruses",
+            Self::WillArriveFirst => "will_arrive_first",
+            Self::EatCrossesOfMyType => "eat_crosses_of_my_type",
+            Self::AvoidInfectedCrosses => "avoid_infected_crosses",
+            Self::Intercept => "intercept",
+            Self::Evade => "evade",
+            Self::Memory => "memory",
+            Self::Herd => "herd",
+            Self::PackHunter => "pack_hunter",
+            Self::Ambush => "ambush",
+            Self::DetectPoison => "detect_poison",
+        }
+    }
+
+    /// The parts of the body's behaviour the skill hooks into.
+    pub const fn hooks(self) -> &'static [SkillHook] {
+        match self {
+            Self::PrioritizeFasterChasers | Self::Evade => {
+                &[SkillHook::ChaseReaction]
+            }
+            Self::Intercept => &[SkillHook::Pursuit],
+            Self::Memory => &[SkillHook::Idle, SkillHook::Sight],
+            Self::Herd => &[SkillHook::Idle, SkillHook::Tick],
+            Self::PackHunter => &[SkillHook::Catch],
+            Self::Ambush => &[SkillHook::Idle, SkillHook::Catch],
+            Self::DoNotCompeteWithRelatives
+            | Self::AliveWhenArrived
+            | Self::ProfitableWhenArrived
+            | Self::AvoidNewViruses
+            | Self::WillArriveFirst
+            | Self::EatCrossesOfMyType
+            | Self::AvoidInfectedCrosses
+            | Self::DetectPoison => &[SkillHook::FoodFilter],
+        }
+    }
+
+    #[inline(always)]
+    pub fn has_hook(self, hook: SkillHook) -> bool {
+        self.hooks().contains(&hook)
+    }
+
+    #[inline(always)]
+    pub fn energy_cost(self) -> f32 {
+        unsafe { SKILL_PROPERTIES[self as usize].energy_cost }
+    }
+
+    #[inline(always)]
+    pub fn inheritance_chance(self) -> f32 {
+        unsafe { SKILL_PROPERTIES[self as usize].inheritance_chance }
+    }
+
+    #[inline(always)]
+    /// Whether the skill lets `body` go for `food`. Called for every skill with
+    /// `SkillHook::FoodFilter`, because some skills restrict bodies that don't have them.
+    pub fn filter_food(
+        self,
+        body: &Body,
+        body_id: &BodyId,
+        food: Food,
+    ) -> bool {
+        match (self, food) {
+            (Self::DoNotCompeteWithRelatives, _) => body
+                .handle_do_not_compete_with_relatives(
+                    body_id,
+                    food.followed_by(),
+                ),
+            (Self::AliveWhenArrived, Food::Body(other_body)) => {
+                body.handle_alive_when_arrived_body(other_body)
+            }
+            (Self::AliveWhenArrived, Food::Cross(cross)) => {
+                body.handle_alive_when_arrived_cross(cross)
+            }
+            (Self::AliveWhenArrived, Food::Plant(plant)) => {
+                body.handle_alive_when_arrived_plant(plant)
+            }
+            (Self::ProfitableWhenArrived, Food::Body(other_body)) => {
+                body.handle_profitable_when_arrived_body(other_body)
+            }
+            (Self::ProfitableWhenArrived, Food::Cross(cross)) => {
+                body.handle_profitable_when_arrived_cross(cross)
+            }
+            (Self::ProfitableWhenArrived, Food::Plant(plant)) => {
+                body.handle_profitable_when_arrived_plant(plant)
+            }
+            (Self::AvoidNewViruses, Food::Cross(cross)) => {
+                body.handle_avoid_new_viruses_cross(cross)
+            }
+            (Self::AvoidNewViruses, Food::Body(other_body)) => {
+                body.handle_avoid_new_viruses_body(other_body)
+            }
+            (Self::WillArriveFirst, Food::Body(other_body)) => body
+                .handle_will_arrive_first_body(body_id, other_body),
+            (Self::WillArriveFirst, Food::Cross(cross)) => {
+                body.handle_will_arrive_first_cross(body_id, cross)
+            }
+            (Self::WillArriveFirst, Food::Plant(plant)) => {
+                body.handle_will_arrive_first_plant(body_id, plant)
+            }
+            (Self::EatCrossesOfMyType, Food::Cross(cross)) => {
+                body.handle_eat_crosses_of_my_type(cross)
+            }
+            (Self::DetectPoison, Food::Plant(plant)) => {
+                body.handle_detect_poison(plant)
+            }
+            _ => true,
+        }
+    }
+
+    #[inline(always)]
+    /// Narrow down the chasers the body escapes from. Called only if the body has the skill.
+    pub fn react_to_chasers(
+        self,
+        body: &Body,
+        chasers: &mut HashMap<BodyId, Body>,
+    ) {
+        if let Self::PrioritizeFasterChasers = self {
+            if chasers
+                .iter()
+                .any(|(_, other_body)| other_body.speed > body.speed)
+            {
+                chasers.retain(|_, other_body| {
+                    other_body.speed > body.speed
+                })
+            }
+        }
+    }
+
+    #[inline(always)]
+    /// The direction the body escapes from `chasers` in. Called only if the body has the skill.
+    pub fn escape_direction(
+        self,
+        body: &Body,
+        chasers: &HashMap<BodyId, Body>,
+        cells: &Cells,
+        plants: &HashMap<Cell, HashMap<PlantId, Plant>>,
+    ) -> Option<Vec2> {
+        if let Self::Evade = self {
+            // Closer and faster chasers push the body harder
+            let mut escape_direction = Vec2::ZERO;
+            for chaser in chasers.values() {
+                escape_direction += (body.pos - chaser.pos)
+                    .normalize_or_zero()
+                    * chaser.speed
+                    / body.pos.distance(chaser.pos).max(MIN_GAP);
+            }
+
+            let escape_direction =
+                escape_direction.normalize_or_zero();
+
+            if escape_direction == Vec2::ZERO {
+                return None;
+            }
+
+            if unsafe { EVADE_FOOD_ATTRACTION } > 0.0 {
+                let mut visible_plants: HashMap<&PlantId, &Plant> =
+                    HashMap::new();
+
+                get_visible!(body, cells, plants, visible_plants);
+
+                // Only the plants that don't make the body turn towards the chasers
+                if let Some(closest_plant) = visible_plants
+                    .values()
+                    .filter(|plant| {
+                        (plant.pos - body.pos).dot(escape_direction)
+                            > 0.0
+                    })
+                    .min_by(|a, b| {
+                        body.pos
+                            .distance(a.pos)
+                            .total_cmp(&body.pos.distance(b.pos))
+                    })
+                {
+                    return Some(
+                        (escape_direction
+                            + (closest_plant.pos - body.pos)
+                                .normalize_or_zero()
+                                * unsafe { EVADE_FOOD_ATTRACTION })
+                        .normalize_or_zero(),
+                    );
+                }
+            }
+
+            Some(escape_direction)
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    /// The point the body aims at when chasing `other_body`. Called only if the body has the
+    /// skill.
+    pub fn aim(self, body: &Body, other_body: &Body) -> Option<Vec2> {
+        if let Self::Intercept = self {
+            body.get_interception(other_body).map(|(pos, _)| pos)
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    /// Make the body do something when it sees no food. Called only if the body has the skill.
+    /// Returns whether the skill has handled the body.
+    pub fn handle_idle(
+        self,
+        body: &mut Body,
+        body_id: &BodyId,
+        cells: &Cells,
+        bodies: &mut HashMap<BodyId, Body>,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+        crosses: &mut HashMap<Cell, HashMap<CrossId, Cross>>,
+        plants: &mut HashMap<Cell, HashMap<PlantId, Plant>>,
+        area_size: &Vec2,
+    ) -> bool {
+        match self {
+            Self::Memory => body.handle_memory_idle(
+                body_id, cells, bodies, crosses, plants, area_size,
+            ),
+            Self::Herd => body.handle_herd_idle(
+                body_id, cells, bodies, body_cells, crosses, plants,
+                area_size,
+            ),
+            Self::Ambush => body.handle_ambush_idle(
+                body_id, cells, bodies, crosses, plants, area_size,
+            ),
+            _ => false,
+        }
+    }
+
+    #[inline(always)]
+    /// Let the body take in the food it sees. Called only if the body has the skill.
+    pub fn see_food(
+        self,
+        body: &mut Body,
+        visible_food: impl Iterator<Item = (Vec2, f32)>,
+    ) {
+        if let Self::Memory = self {
+            body.remember_food(visible_food);
+        }
+    }
+
+    #[inline(always)]
+    /// Update what the body knows about its surroundings. Called only if the body has the
+    /// skill.
+    pub fn update(
+        self,
+        body: &mut Body,
+        body_id: &BodyId,
+        cells: &Cells,
+        body_cells: &HashMap<Cell, HashMap<BodyId, BodyPosition>>,
+    ) {
+        if let Self::Herd = self {
+            body.update_herd_size(body_id, cells, body_cells);
+        }
+    }
+
+    #[inline(always)]
+    /// The energy the body attacks `other_body` with. Called only if the body has the skill.
+    pub fn attack_energy(
+        self,
+        body: &Body,
+        body_id: &BodyId,
+        other_body: &Body,
+    ) -> Option<f32> {
+        if let Self::PackHunter = self {
+            Some(body.get_pack_attack_energy(body_id, other_body))
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    /// The energy the body gets from the caught body. Called only if the body has the skill.
+    pub fn share_caught_body(
+        self,
+        body: &Body,
+        body_id: &BodyId,
+        caught_body_id: &BodyId,
+        bodies: &mut HashMap<BodyId, Body>,
+        removed_bodies: &HashSet<BodyId>,
+    ) -> Option<f32> {
+        if let Self::PackHunter = self {
+            Some(body.share_caught_body(
+                body_id,
+                caught_body_id,
+                bodies,
+                removed_bodies,
+            ))
+        } else {
+            None
+        }
+    }
+
+    #[inline(always)]
+    /// Whether the body waits for the prey instead of going for it. Called only if the
+    /// body has the skill.
+    pub fn wait_for_prey(
+        self,
+        body: &mut Body,
+        food: &FoodInfo,
+    ) -> bool {
+        if let Self::Ambush = self {
+            body.handle_ambush(food)
+        } else {
+            false
+        }
+    }
+}
+
+impl RememberedFood {
+    #[inline(always)]
+    /// How worth it is to walk to the remembered food from `pos`.
+    pub fn get_value(&self, pos: &Vec2) -> f32 {
+        self.energy
+            * (1.0
+                - self.timestamp.elapsed().as_secs_f32()
+                    / unsafe { MEMORY_LIFETIME } as f32)
+            / (pos.distance(self.pos) + 1.0)
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{
+    constants::*, generate_value_noise, load_map_image,
+    user_constants::*, Cells, MapSource,
+};
+use macroquad::prelude::{draw_rectangle, Color, Vec2};
+use std::ptr::addr_of;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TerrainType {
+    Plain,
+    Water,
+    Mud,
+    /// Impassable and blocks the vision.
+    Rock,
+}
+
+impl TerrainType {
+    #[inline(always)]
+    /// How much the terrain slows the bodies down.
+    pub fn get_speed_multiplier(self) -> f32 {
+        match self {
+            Self::Plain | Self::Rock => 1.0,
+            Self::Water => unsafe { WATER_SPEED_MULTIPLIER },
+            Self::Mud => unsafe { MUD_SPEED_MULTIPLIER },
+        }
+    }
+
+    #[inline(always)]
+    /// How much more energy moving across the terrain costs.
+    pub fn get_energy_multiplier(self) -> f32 {
+        match self {
+            Self::Plain | Self::Rock => 1.0,
+            Self::Water => unsafe { WATER_ENERGY_MULTIPLIER },
+            Self::Mud => unsafe { MUD_ENERGY_MULTIPLIER },
+        }
+    }
+
+    #[inline(always)]
+    fn get_color(self) -> Option<Color> {
+        match self {
+            Self::Plain => None,
+            Self::Water => Some(WATER_COLOR),
+            Self::Mud => Some(MUD_COLOR),
+            Self::Rock => Some(ROCK_COLOR),
+        }
+    }
+
+    /// Black is rock, blue is water, brown (reddish) is mud, and the rest is plain.
+    fn from_image_color(color: Color) -> Self {
+        if (color.r + color.g + color.b) / 3.0 < 0.25 {
+            Self::Rock
+        } else if color.b > color.r && color.b > color.g {
+            Self::Water
+        } else if color.r > color.g && color.r > color.b {
+            Self::Mud
+        } else {
+            Self::Plain
+        }
+    }
+
+    fn from_noise(value: f32) -> Self {
+        if value < unsafe { WATER_LEVEL } {
+            Self::Water
+        } else if value < unsafe { MUD_LEVEL } {
+            Self::Mud
+        } else if value > unsafe { ROCK_LEVEL } {
+            Self::Rock
+        } else {
+            Self::Plain
+        }
+    }
+}
+
+/// The terrain type of every cell.
+pub struct TerrainMap {
+    /// Indexed by `i * columns + j`; `None` if everything is plain.
+    types:       Option<Vec<TerrainType>>,
+    rows:        usize,
+    columns:     usize,
+    cell_width:  f32,
+    cell_height: f32,
+}
+
+/// Filled in `main` once the cells are known.
+pub static mut TERRAIN_MAP: TerrainMap = TerrainMap {
+    types:       None,
+    rows:        0,
+    columns:     0,
+    cell_width:  0.0,
+    cell_height: 0.0,
+};
+
+#[inline(always)]
+pub fn terrain_map() -> &'static TerrainMap {
+    unsafe { &*addr_of!(TERRAIN_MAP) }
+}
+
+impl TerrainMap {
+    pub fn new(cells: &Cells) -> Self {
+        let types = match unsafe { TERRAIN_MAP_SOURCE } {
+            MapSource::None => None,
+            MapSource::Noise => Some(
+                generate_value_noise(
+                    cells,
+                    unsafe { TERRAIN_NOISE_SEED },
+                    unsafe { TERRAIN_NOISE_SCALE },
+                )
+                .into_iter()
+                .map(TerrainType::from_noise)
+                .collect(),
+            ),
+            MapSource::Image => Some(
+                load_map_image(cells, TERRAIN_IMAGE_FILE_NAME)
+                    .into_iter()
+                    .map(TerrainType::from_image_color)
+                    .collect(),
+            ),
+        };
+
+        Self {
+            types,
+            rows: cells.rows,
+            columns: cells.columns,
+            cell_width: cells.cell_width,
+            cell_height: cells.cell_height,
+        }
+    }
+
+    #[inline(always)]
+    /// Positions outside the area are plain.
+    pub fn get(&self, pos: &Vec2) -> TerrainType {
+        match &self.types {
+            Some(types) => {
+                if pos.x < 0.0 || pos.y < 0.0 {
+                    return TerrainType::Plain;
+                }
+
+                let (i, j) = (
+                    (pos.y / self.cell_height) as usize,
+                    (pos.x / self.cell_width) as usize,
+                );
+
+                if i >= self.rows || j >= self.columns {
+                    TerrainType::Plain
+                } else {
+                    types[i * self.columns + j]
+                }
+            }
+            None => TerrainType::Plain,
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_blocked(&self, pos: &Vec2) -> bool {
+        self.get(pos) == TerrainType::Rock
+    }
+
+    #[inline(always)]
+    /// Whether there are no rocks between the positions.
+    pub fn is_line_clear(&self, from: &Vec2, to: &Vec2) -> bool {
+        if self.types.is_none() {
+            return true;
+        }
+
+        // Check points along the line often enough not to skip a cell
+        let step = self.cell_width.min(self.cell_height) / 2.0;
+        let steps_n = (from.distance(*to) / step).ceil() as usize;
+
+        (1..steps_n).all(|step_number| {
+            !self.is_blocked(
+                &from.lerp(*to, step_number as f32 / steps_n as f32),
+            )
+        })
+    }
+
+    pub fn draw(&self) {
+        if let Some(types) = &self.types {
+            for (index, terrain_type) in types.iter().enumerate() {
+                if let Some(color) = terrain_type.get_color() {
+                    draw_rectangle(
+                        (index % self.columns) as f32
+                            * self.cell_width,
+                        (index / self.columns) as f32
+                            * self.cell_height,
+                        self.cell_width,
+                        self.cell_height,
+                        color,
+                    );
+                }
+            }
+        }
+    }
+}
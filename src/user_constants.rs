@@ -1,7 +1,14 @@
-use crate::constants::*;
+use crate::{
+    constants::*, Condition, GeneProperties, LineageExportFormat,
+    MapSource, PlantKind, PlantShape, Preferences, ProcreationMode,
+    Skill, SkillProperties, Virus, PLANT_KINDS, SKILL_PROPERTIES,
+};
 use macroquad::prelude::*;
 use serde_derive::Deserialize;
-use std::{fs::read_to_string, ops::Range, process::exit};
+use std::{
+    collections::HashMap, fs::read_to_string, ops::Range,
+    process::exit,
+};
 use toml::from_str;
 
 // Average spawn attributes
@@ -12,27 +19,112 @@ pub static mut AVERAGE_ENERGY: f32 = 0.0;
 pub static mut AVERAGE_SPEED: f32 = 0.0;
 pub static mut AVERAGE_DIVISION_THRESHOLD: f32 = 0.0;
 pub static mut AVERAGE_VISION_DISTANCE: f32 = 0.0;
+pub static mut AVERAGE_VISION_ANGLE: f32 = 0.0;
+pub static mut AVERAGE_SIZE: f32 = 0.0;
+pub static mut AVERAGE_MAX_ACCELERATION: f32 = 0.0;
+pub static mut AVERAGE_MAX_TURNING_RATE: f32 = 0.0;
+pub static mut AVERAGE_LITTER_SIZE: usize = 0;
+pub static mut AVERAGE_LITTER_ENERGY_SHARE: f32 = 0.0;
+pub static mut AVERAGE_PREFERENCES: Preferences = Preferences {
+    body:      0.0,
+    cross:     0.0,
+    plant:     0.0,
+    energy:    0.0,
+    distance:  0.0,
+    infection: 0.0,
+};
 
-pub static mut SKILLS_CHANGE_CHANCE: f32 = 0.0;
 pub static mut PLANTS_DENSITY: f32 = 0.0;
 
 pub static mut DEVIATION: f32 = 0.0;
 pub static mut LIFESPAN: f32 = 0.0;
 pub static mut MIN_ENERGY: f32 = 0.0;
+pub static mut MAX_ENERGY: f32 = 0.0;
+pub static mut MAX_PREY_SIZE_RATIO: f32 = 0.0;
 
 pub static mut PLANT_SPAWN_CHANCE: f32 = 0.0;
 pub static mut PLANT_DIE_CHANCE: f32 = 0.0;
+pub static mut SEEDLING_ENERGY_SHARE: f32 = 0.0;
+pub static mut SEED_DISPERSAL_CHANCE: f32 = 0.0;
+pub static mut SEED_DISPERSAL_RADIUS: f32 = 0.0;
+pub static mut MAX_PLANTS_PER_CELL: usize = 0;
+pub static mut SEED_CARRY_CHANCE: f32 = 0.0;
+pub static mut SEED_CARRY_TIME: f32 = 0.0;
+
+// Fertility
+pub static mut FERTILITY_MAP_SOURCE: MapSource = MapSource::None;
+pub static mut FERTILITY_NOISE_SEED: u64 = 0;
+pub static mut FERTILITY_NOISE_SCALE: usize = 0;
+
+// Terrain
+pub static mut TERRAIN_MAP_SOURCE: MapSource = MapSource::None;
+pub static mut TERRAIN_NOISE_SEED: u64 = 0;
+pub static mut TERRAIN_NOISE_SCALE: usize = 0;
+pub static mut WATER_LEVEL: f32 = 0.0;
+pub static mut MUD_LEVEL: f32 = 0.0;
+pub static mut ROCK_LEVEL: f32 = 0.0;
+pub static mut WATER_SPEED_MULTIPLIER: f32 = 0.0;
+pub static mut WATER_ENERGY_MULTIPLIER: f32 = 0.0;
+pub static mut MUD_SPEED_MULTIPLIER: f32 = 0.0;
+pub static mut MUD_ENERGY_MULTIPLIER: f32 = 0.0;
 
 // Death
 pub static mut CROSS_LIFESPAN: u64 = 0;
 
+// Genome
+pub static mut SPEED_GENE: GeneProperties = GeneProperties::ZERO;
+pub static mut VISION_DISTANCE_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut VISION_ANGLE_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut DIVISION_THRESHOLD_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut SIZE_GENE: GeneProperties = GeneProperties::ZERO;
+pub static mut MAX_ACCELERATION_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut MAX_TURNING_RATE_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut LITTER_SIZE_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut LITTER_ENERGY_SHARE_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut PREFERENCES_GENE: GeneProperties =
+    GeneProperties::ZERO;
+pub static mut SKILLS_CHANGE_CHANCE: f32 = 0.0;
+pub static mut EATING_STRATEGY_CHANGE_CHANCE: f32 = 0.0;
+pub static mut SPECIATION: bool = false;
+pub static mut SPECIATION_DISTANCE: f32 = 0.0;
+
+// Movement
+pub static mut INERTIA: bool = false;
+pub static mut ACCELERATION_ENERGY_COST: f32 = 0.0;
+
+// Procreation
+pub static mut PROCREATION_MODE: ProcreationMode =
+    ProcreationMode::Asexual;
+pub static mut MATING_DISTANCE: f32 = 0.0;
+pub static mut PROCREATION_ENERGY_COST: f32 = 0.0;
+
 // Spending energy
 pub static mut ENERGY_SPENT_CONST_FOR_MASS: f32 = 0.0;
-pub static mut ENERGY_SPENT_CONST_FOR_SKILLS: f32 = 0.0;
 pub static mut ENERGY_SPENT_CONST_FOR_VISION_DISTANCE: f32 = 0.0;
 pub static mut ENERGY_SPENT_CONST_FOR_MOVEMENT: f32 = 0.0;
 pub static mut CONST_FOR_LIFESPAN: f32 = 0.0;
 
+// Skills
+pub static mut EVADE_FOOD_ATTRACTION: f32 = 0.0;
+pub static mut MEMORY_SIZE: usize = 0;
+pub static mut MEMORY_LIFETIME: u64 = 0;
+pub static mut HERD_DISTANCE: f32 = 0.0;
+pub static mut HERD_SEPARATION_DISTANCE: f32 = 0.0;
+pub static mut HERD_PROTECTION: f32 = 0.0;
+pub static mut PACK_SHARE_DISTANCE: f32 = 0.0;
+pub static mut AMBUSH_VISIBILITY: f32 = 0.0;
+pub static mut AMBUSH_IDLE_ENERGY_MULTIPLIER: f32 = 0.0;
+pub static mut AMBUSH_STRIKE_DISTANCE: f32 = 0.0;
+pub static mut AMBUSH_STRIKE_SPEED_MULTIPLIER: f32 = 0.0;
+pub static mut AMBUSH_STRIKE_DURATION: f32 = 0.0;
+
 // SpeedVirus
 pub static mut SPEEDVIRUS_FIRST_GENERATION_INFECTION_CHANCE: f32 =
     0.0;
@@ -50,6 +142,35 @@ pub static mut VISIONVIRUS_HEAL_ENERGY: f32 = 0.0;
 // Condition
 pub static mut CONDITION_CHANCE: f32 = 0.0;
 pub static mut CONDITION_LIFETIME: Range<u64> = 0..0;
+pub static mut REGIONAL_CONDITIONS: bool = false;
+pub static mut CONDITION_RADIUS: [f32; 2] = [0.0, 0.0];
+pub static mut CONDITION_DRIFT_SPEED: f32 = 0.0;
+
+// Drought
+pub static mut DROUGHT_CHANCE: f32 = 0.0;
+pub static mut DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER: f32 = 0.0;
+
+// Rain
+pub static mut RAIN_CHANCE: f32 = 0.0;
+pub static mut RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER: f32 = 0.0;
+
+// Winter
+pub static mut WINTER_CHANCE: f32 = 0.0;
+pub static mut WINTER_ENERGY_SPENT_MULTIPLIER: f32 = 0.0;
+pub static mut WINTER_SPEED_MULTIPLIER: f32 = 0.0;
+
+// Heatwave
+pub static mut HEATWAVE_CHANCE: f32 = 0.0;
+pub static mut HEATWAVE_LIFESPAN_LOSS_MULTIPLIER: f32 = 0.0;
+
+// Fog
+pub static mut FOG_CHANCE: f32 = 0.0;
+pub static mut FOG_VISION_DISTANCE_MULTIPLIER: f32 = 0.0;
+
+// Plague
+pub static mut PLAGUE_CHANCE: f32 = 0.0;
+pub static mut PLAGUE_VIRUS: Virus = Virus::SpeedVirus;
+pub static mut PLAGUE_INFECTION_CHANCE: f32 = 0.0;
 
 // UI
 pub static mut BODY_INFO_FONT_SIZE: u16 = 0;
@@ -62,37 +183,176 @@ pub static mut SHOW_LIFESPAN: bool = false;
 pub static mut SHOW_SKILLS: bool = false;
 pub static mut SHOW_VIRUSES: bool = false;
 
+// Report
+pub static mut STRATEGIES_REPORT_INTERVAL: u64 = 0;
+pub static mut LINEAGE_EXPORT_FORMAT: LineageExportFormat =
+    LineageExportFormat::Newick;
+
 #[derive(Deserialize)]
 struct BodyField {
-    bodies_n:                   usize,
-    passive_chance:             f32,
-    average_energy:             f32,
-    average_speed:              f32,
-    average_division_threshold: f32,
-    average_vision_distance:    f32,
-    skills_change_chance:       f32,
-    deviation:                  f32,
-    lifespan:                   f32,
-    min_energy:                 f32,
-    cross_lifespan:             u64,
-    const_for_lifespan:         f32,
+    bodies_n:                     usize,
+    passive_chance:               f32,
+    average_energy:               f32,
+    average_speed:                f32,
+    average_division_threshold:   f32,
+    average_vision_distance:      f32,
+    average_vision_angle:         f32,
+    average_size:                 f32,
+    average_max_acceleration:     f32,
+    average_max_turning_rate:     f32,
+    average_litter_size:          usize,
+    average_litter_energy_share:  f32,
+    average_body_preference:      f32,
+    average_cross_preference:     f32,
+    average_plant_preference:     f32,
+    average_energy_preference:    f32,
+    average_distance_preference:  f32,
+    average_infection_preference: f32,
+    deviation:                    f32,
+    lifespan:                     f32,
+    min_energy:                   f32,
+    max_energy:                   f32,
+    max_prey_size_ratio:          f32,
+    cross_lifespan:               u64,
+    const_for_lifespan:           f32,
+}
+
+#[derive(Deserialize)]
+struct GeneField {
+    mutation_chance: f32,
+    deviation:       f32,
+    bounds:          [f32; 2],
+}
+
+impl GeneField {
+    fn to_properties(&self, name: &str) -> GeneProperties {
+        if self.bounds[0] > self.bounds[1] {
+            eprintln!(
+                "The bounds of the `{}` gene are in the wrong order.",
+                name
+            );
+            exit(1);
+        }
+
+        GeneProperties {
+            mutation_chance: self.mutation_chance,
+            deviation:       self.deviation,
+            bounds:          (self.bounds[0], self.bounds[1]),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GenomeField {
+    speed:                         GeneField,
+    vision_distance:               GeneField,
+    vision_angle:                  GeneField,
+    division_threshold:            GeneField,
+    size:                          GeneField,
+    max_acceleration:              GeneField,
+    max_turning_rate:              GeneField,
+    litter_size:                   GeneField,
+    litter_energy_share:           GeneField,
+    preferences:                   GeneField,
+    skills_change_chance:          f32,
+    eating_strategy_change_chance: f32,
+    speciation:                    bool,
+    speciation_distance:           f32,
+}
+
+#[derive(Deserialize)]
+struct MovementField {
+    inertia:                  bool,
+    acceleration_energy_cost: f32,
+}
+
+#[derive(Deserialize)]
+struct ProcreationField {
+    procreation_mode:        ProcreationMode,
+    mating_distance:         f32,
+    procreation_energy_cost: f32,
 }
 
 #[derive(Deserialize)]
 struct PlantField {
-    plants_density:     f32,
-    plant_spawn_chance: f32,
-    plant_die_chance:   f32,
+    plants_density:        f32,
+    plant_spawn_chance:    f32,
+    plant_die_chance:      f32,
+    seedling_energy_share: f32,
+    seed_dispersal_chance: f32,
+    seed_dispersal_radius: f32,
+    max_plants_per_cell:   usize,
+    seed_carry_chance:     f32,
+    seed_carry_time:       f32,
+    kinds:                 Vec<PlantKindField>,
+}
+
+#[derive(Deserialize)]
+struct PlantKindField {
+    name:                 String,
+    energy:               f32,
+    spawn_weight:         f32,
+    lifetime:             u64,
+    growth_time:          f32,
+    fertility_range:      [f32; 2],
+    poison_energy_loss:   f32,
+    poison_lifespan_loss: f32,
+    color:                [u8; 3],
+    shape:                PlantShape,
+}
+
+#[derive(Deserialize)]
+struct FertilityField {
+    fertility_map: MapSource,
+    noise_seed:    u64,
+    noise_scale:   usize,
+}
+
+#[derive(Deserialize)]
+struct TerrainField {
+    terrain_map:             MapSource,
+    noise_seed:              u64,
+    noise_scale:             usize,
+    water_level:             f32,
+    mud_level:               f32,
+    rock_level:              f32,
+    water_speed_multiplier:  f32,
+    water_energy_multiplier: f32,
+    mud_speed_multiplier:    f32,
+    mud_energy_multiplier:   f32,
 }
 
 #[derive(Deserialize)]
 struct EnergyField {
     energy_spent_const_for_mass:            f32,
-    energy_spent_const_for_skills:          f32,
     energy_spent_const_for_vision_distance: f32,
     energy_spent_const_for_movement:        f32,
 }
 
+#[derive(Deserialize)]
+struct SkillField {
+    energy_cost:        f32,
+    inheritance_chance: f32,
+}
+
+#[derive(Deserialize)]
+struct SkillsField {
+    evade_food_attraction:          f32,
+    memory_size:                    usize,
+    memory_lifetime:                u64,
+    herd_distance:                  f32,
+    herd_separation_distance:       f32,
+    herd_protection:                f32,
+    pack_share_distance:            f32,
+    ambush_visibility:              f32,
+    ambush_idle_energy_multiplier:  f32,
+    ambush_strike_distance:         f32,
+    ambush_strike_speed_multiplier: f32,
+    ambush_strike_duration:         f32,
+    #[serde(flatten)]
+    properties:                     HashMap<String, SkillField>,
+}
+
 #[derive(Deserialize)]
 struct VirusesField {
     speedvirus_first_generation_infection_chance:  f32,
@@ -119,18 +379,48 @@ pub struct UIField {
 
 #[derive(Deserialize)]
 pub struct ConditionField {
-    condition_chance:   f32,
-    condition_lifetime: [u64; 2],
+    condition_chance:                      f32,
+    condition_lifetime:                    [u64; 2],
+    regional_conditions:                   bool,
+    condition_radius:                      [f32; 2],
+    condition_drift_speed:                 f32,
+    drought_chance:                        f32,
+    drought_plant_die_chance_multiplier:   f32,
+    rain_chance:                           f32,
+    rain_plants_n_for_one_step_multiplier: f32,
+    winter_chance:                         f32,
+    winter_energy_spent_multiplier:        f32,
+    winter_speed_multiplier:               f32,
+    heatwave_chance:                       f32,
+    heatwave_lifespan_loss_multiplier:     f32,
+    fog_chance:                            f32,
+    fog_vision_distance_multiplier:        f32,
+    plague_chance:                         f32,
+    plague_virus:                          Virus,
+    plague_infection_chance:               f32,
+}
+
+#[derive(Deserialize)]
+pub struct ReportField {
+    strategies_report_interval: u64,
+    lineage_export_format:      LineageExportFormat,
 }
 
 #[derive(Deserialize)]
 struct Data {
-    body:      BodyField,
-    plants:    PlantField,
-    energy:    EnergyField,
-    viruses:   VirusesField,
-    condition: ConditionField,
-    ui:        UIField,
+    body:        BodyField,
+    genome:      GenomeField,
+    movement:    MovementField,
+    procreation: ProcreationField,
+    plants:      PlantField,
+    fertility:   FertilityField,
+    terrain:     TerrainField,
+    energy:      EnergyField,
+    skills:      SkillsField,
+    viruses:     VirusesField,
+    condition:   ConditionField,
+    ui:          UIField,
+    report:      ReportField,
 }
 
 pub fn config_setup() {
@@ -151,11 +441,101 @@ pub fn config_setup() {
     };
 
     let body = config.body;
+    let genome = config.genome;
+    let movement = config.movement;
+    let procreation = config.procreation;
     let plants = config.plants;
+    let fertility = config.fertility;
+    let terrain = config.terrain;
     let energy = config.energy;
+    let skills = config.skills;
     let viruses = config.viruses;
     let condition = config.condition;
     let ui = config.ui;
+    let report = config.report;
+
+    if skills.memory_size == 0 || skills.memory_lifetime == 0 {
+        eprintln!(
+            "The memory size and the memory lifetime have to be positive."
+        );
+        exit(1);
+    }
+
+    if terrain.water_level > terrain.mud_level
+        || terrain.mud_level > terrain.rock_level
+    {
+        eprintln!(
+            "The water level, the mud level and the rock level have to go in this order."
+        );
+        exit(1);
+    }
+
+    if condition.condition_radius[0] > condition.condition_radius[1] {
+        eprintln!(
+            "The condition radius range is in the wrong order."
+        );
+        exit(1);
+    }
+
+    let condition_chances = [
+        condition.drought_chance,
+        condition.rain_chance,
+        condition.winter_chance,
+        condition.heatwave_chance,
+        condition.fog_chance,
+        condition.plague_chance,
+    ];
+    assert_eq!(condition_chances.len(), Condition::ALL.len());
+
+    if condition_chances.iter().any(|chance| *chance < 0.0)
+        || condition_chances.iter().sum::<f32>() <= 0.0
+    {
+        eprintln!(
+            "The chances of the conditions can't be negative, and at least one of them has to be positive."
+        );
+        exit(1);
+    }
+
+    if [
+        condition.drought_plant_die_chance_multiplier,
+        condition.rain_plants_n_for_one_step_multiplier,
+        condition.winter_energy_spent_multiplier,
+        condition.winter_speed_multiplier,
+        condition.heatwave_lifespan_loss_multiplier,
+        condition.fog_vision_distance_multiplier,
+    ]
+    .iter()
+    .any(|multiplier| *multiplier < 0.0)
+    {
+        eprintln!(
+            "The multipliers of the conditions can't be negative."
+        );
+        exit(1);
+    }
+
+    if !(0.0..=1.0).contains(&condition.plague_infection_chance) {
+        eprintln!(
+            "The infection chance of the plague has to be between 0 and 1."
+        );
+        exit(1);
+    }
+
+    if body.average_litter_size < 1 {
+        eprintln!("The average litter size has to be at least 1.");
+        exit(1);
+    }
+
+    if genome.litter_size.bounds[0] < 1.0 {
+        eprintln!("The litter size has to be at least 1.");
+        exit(1);
+    }
+
+    if !(0.0..=1.0).contains(&plants.seedling_energy_share) {
+        eprintln!(
+            "The seedling energy share has to be between 0 and 1."
+        );
+        exit(1);
+    }
 
     unsafe {
         // Body-related
@@ -165,18 +545,161 @@ pub fn config_setup() {
         AVERAGE_SPEED = body.average_speed;
         AVERAGE_DIVISION_THRESHOLD = body.average_division_threshold;
         AVERAGE_VISION_DISTANCE = body.average_vision_distance;
+        AVERAGE_VISION_ANGLE = body.average_vision_angle;
+        AVERAGE_SIZE = body.average_size;
+        AVERAGE_MAX_ACCELERATION = body.average_max_acceleration;
+        AVERAGE_MAX_TURNING_RATE = body.average_max_turning_rate;
+        AVERAGE_LITTER_SIZE = body.average_litter_size;
+        AVERAGE_LITTER_ENERGY_SHARE =
+            body.average_litter_energy_share;
+        AVERAGE_PREFERENCES = Preferences {
+            body:      body.average_body_preference,
+            cross:     body.average_cross_preference,
+            plant:     body.average_plant_preference,
+            energy:    body.average_energy_preference,
+            distance:  body.average_distance_preference,
+            infection: body.average_infection_preference,
+        };
         CONST_FOR_LIFESPAN = body.const_for_lifespan;
 
-        SKILLS_CHANGE_CHANCE = body.skills_change_chance;
         DEVIATION = body.deviation;
         LIFESPAN = body.lifespan;
         MIN_ENERGY = body.min_energy;
+        MAX_ENERGY = body.max_energy;
+        MAX_PREY_SIZE_RATIO = body.max_prey_size_ratio;
         CROSS_LIFESPAN = body.cross_lifespan;
 
+        // Genome-related
+        SPEED_GENE = genome.speed.to_properties("speed");
+        VISION_DISTANCE_GENE =
+            genome.vision_distance.to_properties("vision_distance");
+        VISION_ANGLE_GENE =
+            genome.vision_angle.to_properties("vision_angle");
+        DIVISION_THRESHOLD_GENE = genome
+            .division_threshold
+            .to_properties("division_threshold");
+        SIZE_GENE = genome.size.to_properties("size");
+        MAX_ACCELERATION_GENE =
+            genome.max_acceleration.to_properties("max_acceleration");
+        MAX_TURNING_RATE_GENE =
+            genome.max_turning_rate.to_properties("max_turning_rate");
+        LITTER_SIZE_GENE =
+            genome.litter_size.to_properties("litter_size");
+        LITTER_ENERGY_SHARE_GENE = genome
+            .litter_energy_share
+            .to_properties("litter_energy_share");
+        PREFERENCES_GENE =
+            genome.preferences.to_properties("preferences");
+        SKILLS_CHANGE_CHANCE = genome.skills_change_chance;
+        EATING_STRATEGY_CHANGE_CHANCE =
+            genome.eating_strategy_change_chance;
+        SPECIATION = genome.speciation;
+        SPECIATION_DISTANCE = genome.speciation_distance;
+
+        // Movement-related
+        INERTIA = movement.inertia;
+        ACCELERATION_ENERGY_COST = movement.acceleration_energy_cost;
+
+        // Procreation-related
+        PROCREATION_MODE = procreation.procreation_mode;
+        MATING_DISTANCE = procreation.mating_distance;
+        PROCREATION_ENERGY_COST = procreation.procreation_energy_cost;
+
         // Plants-related
         PLANTS_DENSITY = plants.plants_density;
         PLANT_SPAWN_CHANCE = plants.plant_spawn_chance;
         PLANT_DIE_CHANCE = plants.plant_die_chance;
+        SEEDLING_ENERGY_SHARE = plants.seedling_energy_share;
+        SEED_DISPERSAL_CHANCE = plants.seed_dispersal_chance;
+        SEED_DISPERSAL_RADIUS = plants.seed_dispersal_radius;
+        MAX_PLANTS_PER_CELL = plants.max_plants_per_cell;
+        SEED_CARRY_CHANCE = plants.seed_carry_chance;
+        SEED_CARRY_TIME = plants.seed_carry_time;
+
+        if plants.kinds.is_empty() {
+            eprintln!("There has to be at least one plant kind.");
+            exit(1);
+        }
+
+        if plants.kinds.iter().all(|kind| kind.spawn_weight == 0.0) {
+            eprintln!(
+                "At least one plant kind has to have a positive spawn weight."
+            );
+            exit(1);
+        }
+
+        let mut plant_kinds = Vec::with_capacity(plants.kinds.len());
+        for (kind_id, kind) in plants.kinds.iter().enumerate() {
+            if kind.energy < 0.0
+                || kind.spawn_weight < 0.0
+                || kind.growth_time < 0.0
+                || kind.poison_energy_loss < 0.0
+                || kind.poison_lifespan_loss < 0.0
+            {
+                eprintln!(
+                    "The energy, the spawn weight, the growth time and the poison of the `{}` plant kind can't be negative.",
+                    kind.name
+                );
+                exit(1);
+            }
+
+            if kind.fertility_range[0] > kind.fertility_range[1] {
+                eprintln!(
+                    "The fertility range of the `{}` plant kind is in the wrong order.",
+                    kind.name
+                );
+                exit(1);
+            }
+
+            if plants.kinds[..kind_id]
+                .iter()
+                .any(|other_kind| other_kind.name == kind.name)
+            {
+                eprintln!(
+                    "The `{}` plant kind is declared more than once.",
+                    kind.name
+                );
+                exit(1);
+            }
+
+            plant_kinds.push(PlantKind {
+                energy:               kind.energy,
+                spawn_weight:         kind.spawn_weight,
+                lifetime:             kind.lifetime,
+                growth_time:          kind.growth_time,
+                fertility_range:      (
+                    kind.fertility_range[0],
+                    kind.fertility_range[1],
+                ),
+                poison_energy_loss:   kind.poison_energy_loss,
+                poison_lifespan_loss: kind.poison_lifespan_loss,
+                color:                Color::from_rgba(
+                    kind.color[0],
+                    kind.color[1],
+                    kind.color[2],
+                    255,
+                ),
+                shape:                kind.shape,
+            });
+        }
+        PLANT_KINDS = plant_kinds;
+
+        // Fertility-related
+        FERTILITY_MAP_SOURCE = fertility.fertility_map;
+        FERTILITY_NOISE_SEED = fertility.noise_seed;
+        FERTILITY_NOISE_SCALE = fertility.noise_scale;
+
+        // Terrain-related
+        TERRAIN_MAP_SOURCE = terrain.terrain_map;
+        TERRAIN_NOISE_SEED = terrain.noise_seed;
+        TERRAIN_NOISE_SCALE = terrain.noise_scale;
+        WATER_LEVEL = terrain.water_level;
+        MUD_LEVEL = terrain.mud_level;
+        ROCK_LEVEL = terrain.rock_level;
+        WATER_SPEED_MULTIPLIER = terrain.water_speed_multiplier;
+        WATER_ENERGY_MULTIPLIER = terrain.water_energy_multiplier;
+        MUD_SPEED_MULTIPLIER = terrain.mud_speed_multiplier;
+        MUD_ENERGY_MULTIPLIER = terrain.mud_energy_multiplier;
 
         // Virus-related
         SPEEDVIRUS_FIRST_GENERATION_INFECTION_CHANCE =
@@ -197,17 +720,80 @@ pub fn config_setup() {
         // Energy-related
         ENERGY_SPENT_CONST_FOR_MASS =
             energy.energy_spent_const_for_mass;
-        ENERGY_SPENT_CONST_FOR_SKILLS =
-            energy.energy_spent_const_for_skills;
         ENERGY_SPENT_CONST_FOR_VISION_DISTANCE =
             energy.energy_spent_const_for_vision_distance;
         ENERGY_SPENT_CONST_FOR_MOVEMENT =
             energy.energy_spent_const_for_movement;
 
+        // Skill-related
+        EVADE_FOOD_ATTRACTION = skills.evade_food_attraction;
+        MEMORY_SIZE = skills.memory_size;
+        MEMORY_LIFETIME = skills.memory_lifetime;
+        HERD_DISTANCE = skills.herd_distance;
+        HERD_SEPARATION_DISTANCE = skills.herd_separation_distance;
+        HERD_PROTECTION = skills.herd_protection;
+        PACK_SHARE_DISTANCE = skills.pack_share_distance;
+        AMBUSH_VISIBILITY = skills.ambush_visibility;
+        AMBUSH_IDLE_ENERGY_MULTIPLIER =
+            skills.ambush_idle_energy_multiplier;
+        AMBUSH_STRIKE_DISTANCE = skills.ambush_strike_distance;
+        AMBUSH_STRIKE_SPEED_MULTIPLIER =
+            skills.ambush_strike_speed_multiplier;
+        AMBUSH_STRIKE_DURATION = skills.ambush_strike_duration;
+
+        for skill in Skill::ALL {
+            match skills.properties.get(skill.name()) {
+                Some(skill_field) => {
+                    SKILL_PROPERTIES[skill as usize] =
+                        SkillProperties {
+                            energy_cost:        skill_field
+                                .energy_cost,
+                            inheritance_chance: skill_field
+                                .inheritance_chance,
+                        }
+                }
+                None => {
+                    eprintln!(
+                        "The `{}` skill is missing in the config.",
+                        skill.name()
+                    );
+                    exit(1);
+                }
+            }
+        }
+
         // Condition
         CONDITION_CHANCE = condition.condition_chance;
         CONDITION_LIFETIME = condition.condition_lifetime[0]
             ..condition.condition_lifetime[1];
+        REGIONAL_CONDITIONS = condition.regional_conditions;
+        CONDITION_RADIUS = condition.condition_radius;
+        CONDITION_DRIFT_SPEED = condition.condition_drift_speed;
+
+        DROUGHT_CHANCE = condition.drought_chance;
+        DROUGHT_PLANT_DIE_CHANCE_MULTIPLIER =
+            condition.drought_plant_die_chance_multiplier;
+
+        RAIN_CHANCE = condition.rain_chance;
+        RAIN_PLANTS_N_FOR_ONE_STEP_MULTIPLIER =
+            condition.rain_plants_n_for_one_step_multiplier;
+
+        WINTER_CHANCE = condition.winter_chance;
+        WINTER_ENERGY_SPENT_MULTIPLIER =
+            condition.winter_energy_spent_multiplier;
+        WINTER_SPEED_MULTIPLIER = condition.winter_speed_multiplier;
+
+        HEATWAVE_CHANCE = condition.heatwave_chance;
+        HEATWAVE_LIFESPAN_LOSS_MULTIPLIER =
+            condition.heatwave_lifespan_loss_multiplier;
+
+        FOG_CHANCE = condition.fog_chance;
+        FOG_VISION_DISTANCE_MULTIPLIER =
+            condition.fog_vision_distance_multiplier;
+
+        PLAGUE_CHANCE = condition.plague_chance;
+        PLAGUE_VIRUS = condition.plague_virus;
+        PLAGUE_INFECTION_CHANCE = condition.plague_infection_chance;
 
         // UI-related
         BODY_INFO_FONT_SIZE = ui.body_info_font_size;
@@ -219,5 +805,10 @@ pub fn config_setup() {
         SHOW_LIFESPAN = ui.show_lifespan;
         SHOW_SKILLS = ui.show_skills;
         SHOW_VIRUSES = ui.show_viruses;
+
+        // Report-related
+        STRATEGIES_REPORT_INTERVAL =
+            report.strategies_report_interval;
+        LINEAGE_EXPORT_FORMAT = report.lineage_export_format;
     };
 }
@@ -1,13 +1,27 @@
 use crate::{
-    constants::*, user_constants::*, Cells, Condition, Zoom,
+    constants::*, user_constants::*, ActiveCondition, Cells, Zoom,
 };
-use ::rand::{rngs::StdRng, Rng};
+use ::rand::{rngs::StdRng, Rng, SeedableRng};
 use macroquad::prelude::*;
-use std::time::{Duration, Instant};
+use serde_derive::Deserialize;
+use std::{fs::read, process::exit, time::Instant};
+
+/// Where a map over the cells comes from.
+#[derive(Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MapSource {
+    /// The map is the same everywhere.
+    None,
+    /// Generated from value noise with a seed.
+    Noise,
+    /// Loaded from an image.
+    Image,
+}
 
 pub struct LastInfo {
-    pub plants_n: usize,
-    pub bodies_n: usize,
+    pub plants_n:         usize,
+    pub bodies_n:         usize,
+    pub passive_bodies_n: usize,
 }
 
 pub struct EvolutionInfo {
@@ -36,6 +50,82 @@ pub fn generate_zoom_struct(area_size: &Vec2) -> Zoom {
     }
 }
 
+/// Smooth blobs of values from 0.0 to 1.0, one value per cell. `scale` is how many
+/// blobs fit across the area.
+pub fn generate_value_noise(
+    cells: &Cells,
+    seed: u64,
+    scale: usize,
+) -> Vec<f32> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let scale = scale.max(1);
+
+    // Random values in the corners of a coarse grid over the area
+    let grid = (0..(scale + 1).pow(2))
+        .map(|_| rng.gen_range(0.0..1.0))
+        .collect::<Vec<f32>>();
+
+    let smoothstep = |t: f32| t * t * (3.0 - 2.0 * t);
+
+    let mut values = Vec::with_capacity(cells.rows * cells.columns);
+    for i in 0..cells.rows {
+        for j in 0..cells.columns {
+            let y =
+                (i as f32 + 0.5) / cells.rows as f32 * scale as f32;
+            let x = (j as f32 + 0.5) / cells.columns as f32
+                * scale as f32;
+            let (grid_i, grid_j) = (y as usize, x as usize);
+            let (t_y, t_x) =
+                (smoothstep(y.fract()), smoothstep(x.fract()));
+
+            let corner = |di, dj| {
+                grid[(grid_i + di) * (scale + 1) + grid_j + dj]
+            };
+            let top =
+                corner(0, 0) + (corner(0, 1) - corner(0, 0)) * t_x;
+            let bottom =
+                corner(1, 0) + (corner(1, 1) - corner(1, 0)) * t_x;
+
+            values.push(top + (bottom - top) * t_y);
+        }
+    }
+
+    values
+}
+
+/// Get the color of the image under every cell. The image is stretched over the area.
+pub fn load_map_image(cells: &Cells, file_name: &str) -> Vec<Color> {
+    let bytes = match read(file_name) {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            eprintln!("`{}` hasn't been found.", file_name);
+            exit(1);
+        }
+    };
+
+    let image = match Image::from_file_with_format(&bytes, None) {
+        Ok(image) => image,
+        Err(_) => {
+            eprintln!("`{}` couldn't be decoded.", file_name);
+            exit(1);
+        }
+    };
+
+    let mut colors = Vec::with_capacity(cells.rows * cells.columns);
+    for i in 0..cells.rows {
+        for j in 0..cells.columns {
+            colors.push(image.get_pixel(
+                ((j as f32 + 0.5) / cells.columns as f32
+                    * image.width() as f32) as u32,
+                ((i as f32 + 0.5) / cells.rows as f32
+                    * image.height() as f32) as u32,
+            ));
+        }
+    }
+
+    colors
+}
+
 pub fn generate_cells(area_size: &Vec2) -> Cells {
     let mut cells = Cells::default();
 
@@ -66,10 +156,12 @@ pub fn show_evolution_info(
     info: &mut Info,
     plants_n: usize,
     bodies_n: usize,
-    condition: &Option<(Condition, (Instant, Duration))>,
+    passive_bodies_n: usize,
+    condition: &Option<ActiveCondition>,
 ) {
     let plants_n_to_show;
     let bodies_n_to_show;
+    let passive_bodies_n_to_show;
 
     match info.evolution_info.last_info {
         Some(_) => {
@@ -84,13 +176,16 @@ pub fn show_evolution_info(
                 let LastInfo {
                     plants_n: last_plants_n,
                     bodies_n: last_bodies_n,
+                    passive_bodies_n: last_passive_bodies_n,
                 } = info.evolution_info.last_info.as_mut().unwrap();
 
                 *last_plants_n = plants_n;
                 *last_bodies_n = bodies_n;
+                *last_passive_bodies_n = passive_bodies_n;
 
                 plants_n_to_show = plants_n;
                 bodies_n_to_show = bodies_n;
+                passive_bodies_n_to_show = passive_bodies_n;
 
                 info.evolution_info.last_updated =
                     Some(Instant::now());
@@ -98,10 +193,12 @@ pub fn show_evolution_info(
                 let LastInfo {
                     plants_n: last_plants_n,
                     bodies_n: last_bodies_n,
+                    passive_bodies_n: last_passive_bodies_n,
                 } = info.evolution_info.last_info.as_ref().unwrap();
 
                 plants_n_to_show = *last_plants_n;
                 bodies_n_to_show = *last_bodies_n;
+                passive_bodies_n_to_show = *last_passive_bodies_n;
             }
         }
         None => {
@@ -109,11 +206,13 @@ pub fn show_evolution_info(
                 LastInfo {
                     plants_n: plants_n,
                     bodies_n: bodies_n,
+                    passive_bodies_n,
                 }
             });
 
             plants_n_to_show = plants_n;
             bodies_n_to_show = bodies_n;
+            passive_bodies_n_to_show = passive_bodies_n;
 
             info.evolution_info.last_updated = Some(Instant::now());
         }
@@ -122,11 +221,16 @@ pub fn show_evolution_info(
     let evolution_info_fields = [
         format!("plants: {:?}", plants_n_to_show),
         format!("bodies: {:?}", bodies_n_to_show),
+        format!(
+            "passive/active: {:?}/{:?}",
+            passive_bodies_n_to_show,
+            bodies_n_to_show - passive_bodies_n_to_show
+        ),
         format!(
             "condition: {}",
             match condition {
-                Some((condition, _)) => {
-                    format!("{:?}", condition)
+                Some(active_condition) => {
+                    format!("{:?}", active_condition.condition)
                 }
                 None => {
                     "Normal".to_string()